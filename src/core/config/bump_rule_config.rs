@@ -1,16 +1,19 @@
 use std::collections::HashMap;
 
-use crate::{BumpRule, CommitType};
+use crate::{BumpRule, CommitType, CommitTypeAliases};
 
 #[derive(Default, Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct BumpRuleConfig {
     #[serde(flatten)]
-    rules: HashMap<CommitType, BumpRule>,
+    rules: HashMap<String, BumpRule>,
 }
 
 impl BumpRuleConfig {
     pub fn new(rules: &[(CommitType, BumpRule)]) -> Self {
-        let rules = rules.iter().cloned().collect::<HashMap<_, _>>();
+        let rules = rules
+            .iter()
+            .map(|(commit_type, bump_rule)| (commit_type.to_string(), *bump_rule))
+            .collect::<HashMap<_, _>>();
         Self { rules }
     }
 
@@ -19,12 +22,24 @@ impl BumpRuleConfig {
     }
 
     pub fn extend(&mut self, rules: &[(CommitType, BumpRule)]) {
-        self.rules.extend(rules.iter().cloned());
+        self.rules.extend(
+            rules
+                .iter()
+                .map(|(commit_type, bump_rule)| (commit_type.to_string(), *bump_rule)),
+        );
     }
 
-    pub fn iter(&self) -> impl IntoIterator<Item = (&CommitType, &BumpRule)> {
+    pub fn iter(&self) -> impl IntoIterator<Item = (&String, &BumpRule)> {
         self.rules.iter()
     }
+
+    /// The rules with each name read through `aliases`, so a disabled alias names a custom type
+    pub fn resolve(&self, aliases: &CommitTypeAliases) -> Vec<(CommitType, BumpRule)> {
+        self.rules
+            .iter()
+            .map(|(name, bump_rule)| (aliases.resolve(name), *bump_rule))
+            .collect()
+    }
 }
 
 impl IntoIterator for BumpRuleConfig {
@@ -32,13 +47,13 @@ impl IntoIterator for BumpRuleConfig {
     type IntoIter = std::vec::IntoIter<Self::Item>;
 
     fn into_iter(self) -> Self::IntoIter {
-        self.rules.into_iter().collect::<Vec<_>>().into_iter()
+        self.resolve(&CommitTypeAliases::default()).into_iter()
     }
 }
 
 impl<'a> IntoIterator for &'a BumpRuleConfig {
-    type Item = (&'a CommitType, &'a BumpRule);
-    type IntoIter = std::collections::hash_map::Iter<'a, CommitType, BumpRule>;
+    type Item = (&'a String, &'a BumpRule);
+    type IntoIter = std::collections::hash_map::Iter<'a, String, BumpRule>;
 
     fn into_iter(self) -> Self::IntoIter {
         self.rules.iter()
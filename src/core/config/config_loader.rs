@@ -58,6 +58,7 @@ pub fn load_config(path: impl AsRef<Path>) -> Result<SemRelConfig, ConfigError>
         if rules.is_empty() {
             return Err(ConfigError::EmptyConfig(path.as_ref().to_path_buf()));
         }
+        config.validate()?;
         Ok(config)
     } else {
         let path = match find_local_config_path(path).or_else(find_canonical_config_path) {
@@ -91,6 +92,7 @@ pub fn load_config(path: impl AsRef<Path>) -> Result<SemRelConfig, ConfigError>
                 return Err(ConfigError::InvalidConfig(why.to_string()));
             }
         };
+        config.validate()?;
         Ok(config)
     }
 }
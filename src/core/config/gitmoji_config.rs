@@ -0,0 +1,38 @@
+use std::collections::BTreeMap;
+
+use crate::{ConfigError, Gitmoji};
+
+use super::lint_config::is_word;
+
+/// The `[semrel.gitmoji]` section of the configuration
+#[derive(Default, Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GitmojiConfig {
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    enabled: bool,
+    /// `[semrel.gitmoji.types]`: shortcodes or emoji mapped to commit types on top of [`crate::DEFAULT_GITMOJI`]; `""` removes one
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    types: BTreeMap<String, String>,
+}
+
+impl GitmojiConfig {
+    pub fn is_default(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// Checks that every entry names a commit type, optionally followed by `!`
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        for (code, commit_type) in &self.types {
+            let name = commit_type.strip_suffix('!').unwrap_or(commit_type);
+            if !commit_type.is_empty() && !is_word(name) {
+                return Err(ConfigError::InvalidGitmojiConfig(format!("{code}: {commit_type:?} is not a valid commit type")));
+            }
+        }
+        Ok(())
+    }
+
+    /// The gitmoji table, when gitmoji mode is enabled
+    pub fn gitmoji(&self) -> Option<Gitmoji> {
+        self.enabled.then(|| Gitmoji::with_overrides(self.types.clone()))
+    }
+}
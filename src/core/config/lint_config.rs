@@ -0,0 +1,161 @@
+use crate::{BumpRule, CommitType, ConfigError, LintPolicy, ParseOptions, SubjectCase};
+
+/// The `[semrel.lint]` section of the configuration
+#[derive(Default, Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LintConfig {
+    /// Allowed commit types; defaults to the types that have a bump rule
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    types: Vec<String>,
+    /// Allowed scopes; empty allows any scope
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    scopes: Vec<String>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    require_scope: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_subject_length: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_body_line_length: Option<usize>,
+    subject_case: SubjectCase,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    forbid_non_compliant: Option<bool>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    require_issue_ref: bool,
+}
+
+fn invalid(why: impl Into<String>) -> ConfigError {
+    ConfigError::InvalidLintConfig(why.into())
+}
+
+/// True when `value` could be written as a commit type or scope
+pub(super) fn is_word(value: &str) -> bool {
+    !value.is_empty() && !value.chars().any(|c| c.is_whitespace() || matches!(c, ':' | '(' | ')' | '!'))
+}
+
+impl LintConfig {
+    pub fn is_default(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// Checks the section for values that could never match a commit
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        for commit_type in &self.types {
+            if !is_word(commit_type) {
+                return Err(invalid(format!("types: {commit_type:?} is not a valid commit type")));
+            }
+        }
+        for scope in &self.scopes {
+            if !is_word(scope) {
+                return Err(invalid(format!("scopes: {scope:?} is not a valid scope")));
+            }
+            if !matches!(CommitType::from(scope.as_str()), CommitType::Custom(_)) {
+                return Err(invalid(format!("scopes: {scope:?} is a commit type and cannot be used as a scope")));
+            }
+        }
+        if self.max_subject_length == Some(0) {
+            return Err(invalid("max_subject_length must be greater than 0"));
+        }
+        if self.max_body_line_length == Some(0) {
+            return Err(invalid("max_body_line_length must be greater than 0"));
+        }
+        Ok(())
+    }
+
+    /// Builds the lint policy, allowing the types in `rules` unless `types` is set
+    pub fn policy(&self, rules: &[(CommitType, BumpRule)], parsing: &ParseOptions) -> LintPolicy {
+        let defaults = LintPolicy::default();
+        let mut allowed_types = vec![];
+        let types = match self.types.is_empty() {
+            true => rules.iter().map(|(commit_type, _)| commit_type.clone()).collect::<Vec<_>>(),
+            false => self
+                .types
+                .iter()
+                .map(|commit_type| parsing.aliases.resolve(commit_type))
+                .collect(),
+        };
+        for commit_type in types {
+            if !allowed_types.contains(&commit_type) {
+                allowed_types.push(commit_type);
+            }
+        }
+        LintPolicy {
+            allowed_types,
+            allowed_scopes: self.scopes.clone(),
+            require_scope: self.require_scope,
+            max_subject_length: self.max_subject_length.unwrap_or(defaults.max_subject_length),
+            max_body_line_length: self.max_body_line_length,
+            subject_case: self.subject_case,
+            allow_trailing_period: defaults.allow_trailing_period,
+            forbid_non_compliant: self.forbid_non_compliant.unwrap_or(defaults.forbid_non_compliant),
+            require_issue_ref: self.require_issue_ref,
+            parsing: parsing.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[test]
+    fn policy_from_config() {
+        let config: LintConfig = toml::from_str(
+            r#"
+            types = ["feat", "fix", "ENG"]
+            scopes = ["api", "cli"]
+            require_scope = true
+            max_subject_length = 50
+            max_body_line_length = 100
+            subject_case = "lower"
+            forbid_non_compliant = false
+            require_issue_ref = true
+            "#,
+        )
+        .unwrap();
+        config.validate().unwrap();
+
+        let policy = config.policy(&[], &ParseOptions::default());
+
+        assert_eq!(policy.allowed_types, vec![CommitType::Feat, CommitType::Fix, CommitType::Custom("ENG".to_string())]);
+        assert_eq!(policy.allowed_scopes, vec!["api", "cli"]);
+        assert!(policy.require_scope);
+        assert_eq!(policy.max_subject_length, 50);
+        assert_eq!(policy.max_body_line_length, Some(100));
+        assert_eq!(policy.subject_case, SubjectCase::Lower);
+        assert!(!policy.forbid_non_compliant);
+        assert!(policy.require_issue_ref);
+    }
+
+    #[test]
+    fn types_default_to_rules() {
+        let rules = [
+            (CommitType::Feat, BumpRule::Minor),
+            (CommitType::Custom("ENG".to_string()), BumpRule::Patch),
+            (CommitType::Feat, BumpRule::Major),
+        ];
+        let policy = LintConfig::default().policy(&rules, &ParseOptions::default());
+        assert_eq!(policy.allowed_types, vec![CommitType::Feat, CommitType::Custom("ENG".to_string())]);
+        assert_eq!(policy.max_subject_length, LintPolicy::default().max_subject_length);
+        assert!(policy.forbid_non_compliant);
+    }
+
+    #[rstest]
+    #[case::empty_type(r#"types = [""]"#, "types: \"\" is not a valid commit type")]
+    #[case::type_with_space(r#"types = ["new feature"]"#, "types: \"new feature\" is not a valid commit type")]
+    #[case::scope_is_type(r#"scopes = ["feat"]"#, "scopes: \"feat\" is a commit type and cannot be used as a scope")]
+    #[case::zero_subject("max_subject_length = 0", "max_subject_length must be greater than 0")]
+    #[case::zero_body("max_body_line_length = 0", "max_body_line_length must be greater than 0")]
+    fn invalid_config_is_rejected(#[case] toml: &str, #[case] expected: &str) {
+        let config: LintConfig = toml::from_str(toml).unwrap();
+        assert_eq!(config.validate(), Err(ConfigError::InvalidLintConfig(expected.to_string())));
+    }
+
+    #[rstest]
+    #[case::unknown_field("max_length = 50", "unknown field `max_length`")]
+    #[case::unknown_case(r#"subject_case = "title""#, "unknown variant `title`")]
+    fn unknown_values_are_rejected(#[case] toml: &str, #[case] expected: &str) {
+        let why = toml::from_str::<LintConfig>(toml).unwrap_err().to_string();
+        assert!(why.contains(expected), "{why}");
+    }
+}
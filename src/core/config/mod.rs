@@ -1,7 +1,17 @@
 mod bump_rule_config;
 mod config_loader;
+mod gitmoji_config;
+mod lint_config;
+mod pattern_config;
 mod semrel_config;
+mod strict_config;
+mod type_config;
 
 pub use bump_rule_config::BumpRuleConfig;
 pub use config_loader::{DEFAULT_CONFIG_FILENAME, find_canonical_config_path, find_local_config_path, load_config};
-pub use semrel_config::SemRelConfig;
+pub use gitmoji_config::GitmojiConfig;
+pub use lint_config::LintConfig;
+pub use pattern_config::PatternConfig;
+pub use semrel_config::{DEFAULT_TAG_FORMAT, SemRelConfig};
+pub use strict_config::StrictConfig;
+pub use type_config::TypeConfig;
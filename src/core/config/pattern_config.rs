@@ -0,0 +1,31 @@
+use crate::{CommitPattern, ConfigError};
+
+/// A `[[semrel.patterns]]` entry of the configuration
+#[derive(Default, Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PatternConfig {
+    /// Matched against the first line of the message; may capture `type`, `scope`, `subject` and `breaking`
+    regex: String,
+    /// The commit type when `regex` has no `type` capture
+    #[serde(default, rename = "type", skip_serializing_if = "Option::is_none")]
+    commit_type: Option<String>,
+    /// The scope when `regex` has no `scope` capture
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    scope: Option<String>,
+    /// Try the pattern before the conventional commit grammar, e.g. for `ENG-1234: handle timeout`
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    before_grammar: bool,
+}
+
+impl PatternConfig {
+    pub fn pattern(&self) -> Result<CommitPattern, ConfigError> {
+        let invalid = |why: String| ConfigError::InvalidPatternConfig(why);
+        let pattern = CommitPattern::new(&self.regex, self.commit_type.clone(), self.scope.clone())
+            .map_err(|why| invalid(format!("{:?}: {why}", self.regex)))?
+            .with_before_grammar(self.before_grammar);
+        if !pattern.has_type() {
+            return Err(invalid(format!("{:?} needs a `type` capture or a `type` key", self.regex)));
+        }
+        Ok(pattern)
+    }
+}
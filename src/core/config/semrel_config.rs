@@ -1,4 +1,13 @@
-use crate::{BumpRule, BumpRuleConfig, CommitType};
+use std::collections::BTreeMap;
+
+use super::lint_config::is_word;
+use crate::{
+    BUILTIN_ALIASES, BumpRule, BumpRuleConfig, CommitType, CommitTypeAliases, ConfigError, GitmojiConfig, HistoryMode, LintConfig, LintPolicy, ParseOptions, PathFilters, PatternConfig,
+    StrictAllowlist, StrictConfig, TypeConfig, TypeDefinition,
+};
+
+/// The tag name template used when no `tag_format` is configured
+pub const DEFAULT_TAG_FORMAT: &str = "v{version}";
 
 #[derive(Default, Debug, serde::Serialize, serde::Deserialize)]
 
@@ -9,6 +18,36 @@ pub struct SemRelConfig {
 #[derive(Default, Debug, serde::Serialize, serde::Deserialize)]
 pub struct SemRel {
     rules: BumpRuleConfig,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    tag_format: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    history: Option<HistoryMode>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    include: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    exclude: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    release_branches: Vec<String>,
+    /// Built-in aliases to turn off, e.g. `deploy`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    disabled_aliases: Vec<String>,
+    #[serde(default, skip_serializing_if = "LintConfig::is_default")]
+    lint: LintConfig,
+    /// Extra spellings of commit types, e.g. `bugfix = "fix"`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    aliases: BTreeMap<String, String>,
+    /// Commit types with their own release notes title, bump, order and visibility
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    types: BTreeMap<String, TypeConfig>,
+    /// Reads gitmoji such as `:sparkles:` as commit types
+    #[serde(default, skip_serializing_if = "GitmojiConfig::is_default")]
+    gitmoji: GitmojiConfig,
+    /// Regular expressions that read non-conventional headers, tried in order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    patterns: Vec<PatternConfig>,
+    /// Rejects releases that contain non-conventional commits
+    #[serde(default, skip_serializing_if = "StrictConfig::is_default")]
+    strict: StrictConfig,
 }
 
 impl SemRel {
@@ -26,11 +65,359 @@ impl SemRelConfig {
         self.semrel.has_rules()
     }
 
+    /// The `[semrel.rules]`, with rule names read through [`SemRelConfig::aliases`]
     pub fn rules(&self) -> impl IntoIterator<Item = (CommitType, BumpRule)> {
-        self.semrel.rules.clone().into_iter()
+        self.semrel.rules.resolve(&self.aliases())
     }
 
     pub fn extend_rules(&mut self, rules: &[(CommitType, BumpRule)]) {
         self.semrel.extend_rules(rules);
     }
+
+    /// The template used to name release tags, e.g. `v{version}`
+    pub fn tag_format(&self) -> &str {
+        self.semrel.tag_format.as_deref().unwrap_or(DEFAULT_TAG_FORMAT)
+    }
+
+    /// How merge commits are traversed when collecting the changelog
+    pub fn history(&self) -> HistoryMode {
+        self.semrel.history.unwrap_or_default()
+    }
+
+    /// The branches (glob patterns) that `update` and `release` may run on; empty allows any branch
+    pub fn release_branches(&self) -> &[String] {
+        &self.semrel.release_branches
+    }
+
+    /// The `semrel lint` policy; commit types default to those in `rules` and `[semrel.types]`
+    pub fn lint_policy(&self, rules: &[(CommitType, BumpRule)]) -> LintPolicy {
+        let mut known = rules.to_vec();
+        known.extend(
+            self.types()
+                .into_iter()
+                .map(|definition| (definition.commit_type, definition.bump.unwrap_or_default())),
+        );
+        self.semrel.lint.policy(&known, &self.parse_options())
+    }
+
+    /// The commit type aliases from `[semrel.aliases]` and `disabled_aliases`
+    pub fn aliases(&self) -> CommitTypeAliases {
+        let aliases = self
+            .semrel
+            .aliases
+            .iter()
+            .map(|(alias, commit_type)| (alias.clone(), CommitType::from(commit_type.as_str())));
+        CommitTypeAliases::new(aliases, self.semrel.disabled_aliases.iter().cloned())
+    }
+
+    /// How commit messages are read: the aliases, gitmoji and the `[[semrel.patterns]]`
+    ///
+    /// Patterns that do not compile are left out; [`SemRelConfig::validate`] reports them.
+    pub fn parse_options(&self) -> ParseOptions {
+        ParseOptions {
+            aliases: self.aliases(),
+            patterns: self
+                .semrel
+                .patterns
+                .iter()
+                .filter_map(|pattern| pattern.pattern().ok())
+                .collect(),
+            gitmoji: self.semrel.gitmoji.gitmoji(),
+        }
+    }
+
+    /// The commit types from `[semrel.types]`
+    pub fn types(&self) -> Vec<TypeDefinition> {
+        let aliases = self.aliases();
+        self.semrel
+            .types
+            .iter()
+            .map(|(name, config)| config.definition(aliases.resolve(name)))
+            .collect()
+    }
+
+    /// The bumps of the types in `[semrel.types]`, ranked below `[semrel.rules]`
+    pub fn type_rules(&self) -> Vec<(CommitType, BumpRule)> {
+        self.types()
+            .into_iter()
+            .filter_map(|definition| Some((definition.commit_type, definition.bump?)))
+            .collect()
+    }
+
+    /// Checks settings that parse but could never work
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        self.semrel.lint.validate()?;
+        for (name, config) in &self.semrel.types {
+            config.validate(name)?;
+        }
+        for pattern in &self.semrel.patterns {
+            pattern.pattern()?;
+        }
+        self.semrel.gitmoji.validate()?;
+        self.semrel.strict.validate()?;
+        let invalid = |why: String| ConfigError::InvalidAliasConfig(why);
+        for (alias, commit_type) in &self.semrel.aliases {
+            if !is_word(alias) {
+                return Err(invalid(format!("{alias:?} is not a valid commit type")));
+            }
+            if !is_word(commit_type) {
+                return Err(invalid(format!("{alias}: {commit_type:?} is not a valid commit type")));
+            }
+            let builtin = CommitType::from(alias.as_str());
+            if !matches!(builtin, CommitType::Custom(_)) && builtin.as_str() == alias.to_lowercase() {
+                return Err(invalid(format!("{alias:?} is a standard commit type and cannot be an alias")));
+            }
+        }
+        for alias in &self.semrel.disabled_aliases {
+            if !BUILTIN_ALIASES.iter().any(|(builtin, _)| builtin.eq_ignore_ascii_case(alias)) {
+                let known = BUILTIN_ALIASES
+                    .iter()
+                    .map(|(builtin, _)| *builtin)
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                return Err(invalid(format!("disabled_aliases: {alias:?} is not a built-in alias ({known})")));
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether `[semrel.strict]` is enabled
+    pub fn strict(&self) -> bool {
+        self.semrel.strict.enabled()
+    }
+
+    /// The merges and authors that strict mode lets through
+    pub fn strict_allowlist(&self) -> StrictAllowlist {
+        self.semrel.strict.allowlist()
+    }
+
+    /// The include and exclude patterns, relative to the project directory
+    pub fn path_filters(&self) -> PathFilters {
+        PathFilters {
+            include: self.semrel.include.clone(),
+            exclude: self.semrel.exclude.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[test]
+    fn aliases_from_config() {
+        let config: SemRelConfig = toml::from_str(
+            r#"
+            [semrel]
+            disabled_aliases = ["deploy"]
+
+            [semrel.rules]
+            feat = "minor"
+
+            [semrel.aliases]
+            bugfix = "fix"
+            enhancement = "feat"
+            "#,
+        )
+        .unwrap();
+        config.validate().unwrap();
+
+        let aliases = config.aliases();
+
+        assert_eq!(aliases.resolve("bugfix"), CommitType::Fix);
+        assert_eq!(aliases.resolve("Enhancement"), CommitType::Feat);
+        assert_eq!(aliases.resolve("deploy"), CommitType::Custom("deploy".to_string()));
+        assert_eq!(aliases.resolve("feature"), CommitType::Feat);
+    }
+
+    #[test]
+    fn rules_follow_disabled_aliases() {
+        let config: SemRelConfig = toml::from_str("[semrel]\ndisabled_aliases = [\"deploy\"]\n\n[semrel.rules]\ndeploy = \"minor\"\n").unwrap();
+        config.validate().unwrap();
+        let rules = config
+            .rules()
+            .into_iter()
+            .chain(crate::build_default_rules())
+            .collect::<Vec<_>>();
+        let commit = crate::ConventionalCommit::parse_with("deploy: ship", &config.parse_options()).unwrap();
+        let changelog = crate::ChangeLog::new(crate::SimpleVersion::new(0, 1, 0), [crate::CommitInfo::new("abc1234", ["src/lib.rs"], commit, 0)]);
+
+        assert_eq!(changelog.next_version(&rules).to_string(), "0.2.0");
+    }
+
+    #[test]
+    fn types_from_config() {
+        let config: SemRelConfig = toml::from_str(
+            r#"
+            [semrel.rules]
+            deps = "minor"
+
+            [semrel.types.security]
+            title = "Security"
+            bump = "patch"
+            order = 15
+
+            [semrel.types.deps]
+            bump = "patch"
+            hidden = true
+            "#,
+        )
+        .unwrap();
+        config.validate().unwrap();
+
+        let security = CommitType::Custom("security".to_string());
+        let deps = CommitType::Custom("deps".to_string());
+        assert_eq!(
+            config.types(),
+            vec![
+                TypeDefinition {
+                    hidden: true,
+                    bump: Some(BumpRule::Patch),
+                    ..TypeDefinition::new(deps.clone())
+                },
+                TypeDefinition {
+                    title: Some("Security".to_string()),
+                    bump: Some(BumpRule::Patch),
+                    order: Some(15),
+                    ..TypeDefinition::new(security.clone())
+                },
+            ]
+        );
+        assert_eq!(config.type_rules(), vec![(deps.clone(), BumpRule::Patch), (security.clone(), BumpRule::Patch)]);
+        assert!(config.lint_policy(&[]).allowed_types.contains(&security));
+    }
+
+    #[rstest]
+    #[case::type_with_space("[semrel.types.\"dep bump\"]", ConfigError::InvalidTypeConfig("\"dep bump\" is not a valid commit type".to_string()))]
+    #[case::empty_title("[semrel.types.deps]\ntitle = \" \"", ConfigError::InvalidTypeConfig("deps: title must not be empty".to_string()))]
+    fn invalid_types_are_rejected(#[case] toml: &str, #[case] expected: ConfigError) {
+        let config: SemRelConfig = toml::from_str(&format!("[semrel.rules]\n{toml}\n")).unwrap();
+        assert_eq!(config.validate().unwrap_err(), expected);
+    }
+
+    #[test]
+    fn unknown_type_keys_are_rejected() {
+        let why = toml::from_str::<SemRelConfig>("[semrel.rules]\n[semrel.types.deps]\nheading = \"Deps\"\n")
+            .unwrap_err()
+            .to_string();
+        assert!(why.contains("unknown field `heading`"), "{why}");
+    }
+
+    #[test]
+    fn patterns_from_config() {
+        let config: SemRelConfig = toml::from_str(
+            r#"
+            [semrel.rules]
+            [semrel.aliases]
+            bugfix = "fix"
+
+            [[semrel.patterns]]
+            regex = '^(?P<scope>[A-Z]+-\d+): (?P<subject>.+)$'
+            type = "bugfix"
+            before_grammar = true
+
+            [[semrel.patterns]]
+            regex = '^Added '
+            type = "feat"
+            scope = "legacy"
+            "#,
+        )
+        .unwrap();
+        config.validate().unwrap();
+
+        let options = config.parse_options();
+
+        assert_eq!(options.patterns.len(), 2);
+        assert_eq!(options.rewrite_before_grammar("ENG-12: handle timeout").as_deref(), Some("bugfix(ENG-12): handle timeout"));
+        assert_eq!(options.rewrite_before_grammar("Added login"), None);
+        let commit = crate::ConventionalCommit::parse_with("ENG-12: handle timeout", &options).unwrap();
+        assert_eq!(commit.commit_type, CommitType::Fix);
+        assert_eq!(options.rewrite("Added login").as_deref(), Some("feat(legacy): Added login"));
+    }
+
+    #[test]
+    fn gitmoji_from_config() {
+        let config: SemRelConfig = toml::from_str("[semrel.rules]\n[semrel.gitmoji]\nenabled = true\n\n[semrel.gitmoji.types]\ntada = \"feat\"\nmemo = \"\"\n").unwrap();
+        config.validate().unwrap();
+
+        let options = config.parse_options();
+
+        assert_eq!(options.rewrite(":tada: first commit").as_deref(), Some("feat: first commit"));
+        assert_eq!(options.rewrite(":sparkles: add login").as_deref(), Some("feat: add login"));
+        assert_eq!(options.rewrite(":memo: readme"), None);
+    }
+
+    #[test]
+    fn gitmoji_is_off_unless_enabled() {
+        let config: SemRelConfig = toml::from_str("[semrel.rules]\n[semrel.gitmoji.types]\ntada = \"feat\"\n").unwrap();
+        config.validate().unwrap();
+
+        assert_eq!(config.parse_options().gitmoji, None);
+    }
+
+    #[test]
+    fn gitmoji_types_are_checked() {
+        let config: SemRelConfig = toml::from_str("[semrel.rules]\n[semrel.gitmoji]\nenabled = true\n\n[semrel.gitmoji.types]\ntada = \"new feature\"\n").unwrap();
+
+        assert_eq!(
+            config.validate().unwrap_err(),
+            ConfigError::InvalidGitmojiConfig("tada: \"new feature\" is not a valid commit type".to_string())
+        );
+    }
+
+    #[rstest]
+    #[case::misspelled_key("[semrel.gitmoji]\nenable = true")]
+    #[case::entry_outside_types("[semrel.gitmoji]\nenabled = true\ntada = \"feat\"")]
+    fn gitmoji_rejects_unknown_keys(#[case] toml: &str) {
+        assert!(toml::from_str::<SemRelConfig>(&format!("[semrel.rules]\n{toml}\n")).is_err());
+    }
+
+    #[test]
+    fn strict_from_config() {
+        let config: SemRelConfig = toml::from_str("[semrel.rules]\n[semrel.strict]\nenabled = true\nallow_merges = true\nallow_authors = [\"dependabot[bot]\"]\n").unwrap();
+        config.validate().unwrap();
+
+        assert!(config.strict());
+        assert_eq!(
+            config.strict_allowlist(),
+            StrictAllowlist {
+                merges: true,
+                authors: vec![glob::Pattern::new("dependabot[bot]").unwrap()],
+            }
+        );
+        assert!(!SemRelConfig::default().strict());
+        assert!(toml::from_str::<SemRelConfig>("[semrel.rules]\n[semrel.strict]\nallow_bots = true\n").is_err());
+    }
+
+    #[rstest]
+    #[case::empty("\" \"", "allow_authors must not contain empty entries")]
+    #[case::bad_glob("\"[\"", "allow_authors: \"[\" is not a valid glob pattern: Pattern syntax error near position 0: invalid range pattern")]
+    fn invalid_allow_authors_are_rejected(#[case] author: &str, #[case] expected: &str) {
+        let config: SemRelConfig = toml::from_str(&format!("[semrel.rules]\n[semrel.strict]\nallow_authors = [{author}]\n")).unwrap();
+
+        assert_eq!(config.validate().unwrap_err(), ConfigError::InvalidStrictConfig(expected.to_string()));
+    }
+
+    #[rstest]
+    #[case::bad_regex("regex = '^(ENG'", "\"^(ENG\": regex parse error")]
+    #[case::no_type("regex = '^(?P<subject>.+)$'", "\"^(?P<subject>.+)$\" needs a `type` capture or a `type` key")]
+    fn invalid_patterns_are_rejected(#[case] toml: &str, #[case] expected: &str) {
+        let config: SemRelConfig = toml::from_str(&format!("[semrel.rules]\n[[semrel.patterns]]\n{toml}\n")).unwrap();
+        let why = config.validate().unwrap_err();
+        assert!(matches!(&why, ConfigError::InvalidPatternConfig(message) if message.starts_with(expected)), "{why}");
+    }
+
+    #[rstest]
+    #[case::alias_with_space("[semrel.aliases]\n\"bug fix\" = \"fix\"", "\"bug fix\" is not a valid commit type")]
+    #[case::empty_target("[semrel.aliases]\nbugfix = \"\"", "bugfix: \"\" is not a valid commit type")]
+    #[case::standard_type("[semrel.aliases]\nfeat = \"fix\"", "\"feat\" is a standard commit type and cannot be an alias")]
+    #[case::unknown_disabled(
+        "[semrel]\ndisabled_aliases = [\"bugfix\"]",
+        "disabled_aliases: \"bugfix\" is not a built-in alias (continuous integration, deploy, doc, documentation, feature, features, fixes, performance, tests)"
+    )]
+    fn invalid_aliases_are_rejected(#[case] toml: &str, #[case] expected: &str) {
+        let config: SemRelConfig = toml::from_str(&format!("{toml}\n[semrel.rules]\n")).unwrap();
+        assert_eq!(config.validate().unwrap_err(), ConfigError::InvalidAliasConfig(expected.to_string()));
+    }
 }
@@ -0,0 +1,51 @@
+use crate::{ConfigError, StrictAllowlist};
+
+/// The `[semrel.strict]` section of the configuration
+#[derive(Default, Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StrictConfig {
+    /// Fail `show next`, `update` and `release` when a change is not a conventional commit
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    enabled: bool,
+    /// Let merge commits through
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    allow_merges: bool,
+    /// Author names or emails (exact or glob patterns) whose commits are let through, e.g. `dependabot[bot]`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    allow_authors: Vec<String>,
+}
+
+impl StrictConfig {
+    pub fn is_default(&self) -> bool {
+        self == &Self::default()
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Checks that every author entry is a non-empty glob pattern
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if self.allow_authors.iter().any(|author| author.trim().is_empty()) {
+            return Err(ConfigError::InvalidStrictConfig("allow_authors must not contain empty entries".to_string()));
+        }
+        for author in &self.allow_authors {
+            glob::Pattern::new(author).map_err(|why| ConfigError::InvalidStrictConfig(format!("allow_authors: {author:?} is not a valid glob pattern: {why}")))?;
+        }
+        Ok(())
+    }
+
+    /// The compiled allowlist
+    ///
+    /// Author entries that do not compile are left out; [`StrictConfig::validate`] reports them.
+    pub fn allowlist(&self) -> StrictAllowlist {
+        StrictAllowlist {
+            merges: self.allow_merges,
+            authors: self
+                .allow_authors
+                .iter()
+                .filter_map(|author| glob::Pattern::new(author).ok())
+                .collect(),
+        }
+    }
+}
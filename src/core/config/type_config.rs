@@ -0,0 +1,43 @@
+use crate::{BumpRule, CommitType, ConfigError, TypeDefinition};
+
+use super::lint_config::is_word;
+
+/// A `[semrel.types.<name>]` section of the configuration
+#[derive(Default, Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TypeConfig {
+    /// The release notes heading, e.g. `Security`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    title: Option<String>,
+    /// The bump used when no rule names the type
+    #[serde(skip_serializing_if = "Option::is_none")]
+    bump: Option<BumpRule>,
+    /// Where the section appears in the release notes; the standard types use 10 (feat) to 120 (docs)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    order: Option<i64>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    hidden: bool,
+}
+
+impl TypeConfig {
+    /// Checks the section for `name` for values that could never work
+    pub fn validate(&self, name: &str) -> Result<(), ConfigError> {
+        if !is_word(name) {
+            return Err(ConfigError::InvalidTypeConfig(format!("{name:?} is not a valid commit type")));
+        }
+        if self.title.as_deref().is_some_and(|title| title.trim().is_empty()) {
+            return Err(ConfigError::InvalidTypeConfig(format!("{name}: title must not be empty")));
+        }
+        Ok(())
+    }
+
+    pub fn definition(&self, commit_type: CommitType) -> TypeDefinition {
+        TypeDefinition {
+            commit_type,
+            title: self.title.clone(),
+            bump: self.bump,
+            order: self.order,
+            hidden: self.hidden,
+        }
+    }
+}
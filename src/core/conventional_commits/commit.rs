@@ -4,9 +4,9 @@ use std::path::Path;
 use git2::Commit;
 use pest::Parser;
 
-use crate::{ConventionalCommitError, get_recent_commit, prune_message};
+use crate::{BumpRule, ConventionalCommitError, SimpleVersion, get_recent_commit, prune_message};
 
-use super::{CommitMessageParser, CommitType, Rule};
+use super::{CommitMessageParser, CommitType, CommitTypeAliases, ParseDiagnostic, ParseOptions, Rule};
 
 #[derive(Debug, Default, serde::Deserialize, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct ConventionalCommit {
@@ -17,40 +17,134 @@ pub struct ConventionalCommit {
     pub body: Option<String>,
     pub prefix: Option<String>,
     pub breaking_change: bool,
+    /// The `(token, value)` pairs of the footer, in order, e.g. `("Refs", "#12")`
+    #[serde(default)]
+    pub footers: Vec<(String, String)>,
+    /// The text of each `BREAKING CHANGE:` in the body and footer, in order
+    #[serde(default)]
+    pub breaking_changes: Vec<String>,
+}
+
+const BREAKING_TOKENS: [&str; 2] = ["BREAKING CHANGE", "BREAKING-CHANGE"];
+/// The shortest abbreviated commit id accepted in a `This reverts commit <sha>` line, as git abbreviates by default
+const MIN_REVERTED_ID_LENGTH: usize = 7;
+
+/// Splits a footer line into `(token, value)` when it starts a new footer
+///
+/// Tokens use `-` in place of spaces (except `BREAKING CHANGE`) and are followed by `: `
+/// or ` #`, as in `Refs: #12` or `Closes #4`.  The `#` is kept as part of the value.
+fn footer_start(line: &str) -> Option<(String, String)> {
+    for token in BREAKING_TOKENS {
+        if let Some(value) = line.strip_prefix(token).and_then(|rest| rest.strip_prefix(':')) {
+            return Some((token.to_string(), value.trim().to_string()));
+        }
+    }
+    let is_token = |token: &str| !token.is_empty() && token.chars().all(|c| c.is_alphanumeric() || c == '-');
+    if let Some((token, value)) = line
+        .split_once(": ")
+        .or_else(|| line.strip_suffix(':').map(|token| (token, "")))
+    {
+        if is_token(token) {
+            return Some((token.to_string(), value.trim().to_string()));
+        }
+    }
+    match line.split_once(" #") {
+        Some((token, value)) if is_token(token) => Some((token.to_string(), format!("#{}", value.trim()))),
+        _ => None,
+    }
+}
+
+/// Parses a footer paragraph into its `(token, value)` pairs
+///
+/// Lines that do not start a new footer continue the value of the previous one.  A paragraph
+/// that does not start with a footer token has no footers.
+fn parse_footers(section: &str) -> Vec<(String, String)> {
+    let mut footers: Vec<(String, String)> = vec![];
+    for line in section.lines() {
+        match (footer_start(line), footers.last_mut()) {
+            (Some(footer), _) => footers.push(footer),
+            (None, Some((_, value))) => {
+                value.push('\n');
+                value.push_str(line);
+            }
+            (None, None) => return vec![],
+        }
+    }
+    footers
 }
 
 impl ConventionalCommit {
     pub fn new(commit_message: impl AsRef<str>) -> Result<Self, ConventionalCommitError> {
+        ConventionalCommit::parse_with(commit_message, &ParseOptions::default())
+    }
+
+    /// Parses a commit message with the configured aliases, gitmoji and patterns
+    ///
+    /// Patterns marked to run before the grammar are tried first.  Gitmoji and the other
+    /// patterns only read messages the grammar finds non-compliant.  A header they match is read
+    /// as the conventional header they build; when that does not parse, or nothing matches, the
+    /// message is kept as the grammar read it.
+    pub fn parse_with(commit_message: impl AsRef<str>, options: &ParseOptions) -> Result<Self, ConventionalCommitError> {
+        let message = commit_message.as_ref();
+        if let Some(commit) = options
+            .rewrite_before_grammar(message)
+            .and_then(|rewritten| ConventionalCommit::parse_rewritten(&rewritten, &options.aliases))
+        {
+            return Ok(commit);
+        }
+        let commit = ConventionalCommit::parse_message(message, &options.aliases)?;
+        if commit.commit_type != CommitType::NonCompliant {
+            return Ok(commit);
+        }
+        Ok(options
+            .rewrite(message)
+            .and_then(|rewritten| ConventionalCommit::parse_rewritten(&rewritten, &options.aliases))
+            .unwrap_or(commit))
+    }
+
+    /// Parses a header built by gitmoji or a pattern, or `None` when it does not parse
+    fn parse_rewritten(rewritten: &str, aliases: &CommitTypeAliases) -> Option<Self> {
+        ConventionalCommit::parse_message(rewritten, aliases)
+            .inspect_err(|why| tracing::debug!("Ignoring pattern match {rewritten:?}: {why}"))
+            .ok()
+    }
+
+    fn parse_message(commit_message: impl AsRef<str>, aliases: &CommitTypeAliases) -> Result<Self, ConventionalCommitError> {
         if commit_message.as_ref().trim().is_empty() {
             return Err(ConventionalCommitError::EmptyCommitMessage);
         }
-        let pruned_message = {
-            let new_message = prune_message(commit_message.as_ref());
-            match new_message.is_empty() {
-                true => {
-                    tracing::trace!("Pruned message empty.  Using original commit message: {:?}", commit_message.as_ref());
-                    commit_message.as_ref().to_owned()
-                }
-                false => new_message,
-            }
-        };
-        let parsed = CommitMessageParser::parse(Rule::commit_message, &pruned_message).map_err(|err| ConventionalCommitError::InvalidCommitMessage(err.to_string()))?;
+        let pruned_message = parsed_text(commit_message.as_ref());
+        let parsed = CommitMessageParser::parse(Rule::commit_message, &pruned_message).map_err(|err| ConventionalCommitError::InvalidCommitMessage(ParseDiagnostic::from_pest(&err)))?;
         let mut commit = ConventionalCommit::default();
+        // The grammar consumes a leading `BREAKING CHANGE:` before the section it introduces
+        let mut breaking_token = None;
 
         for inner in parsed.into_iter() {
             match inner.as_rule() {
                 Rule::breaking_change_shorthand => commit.breaking_change = true,
-                Rule::breaking_change_phrase => commit.breaking_change = true,
-                Rule::commit_type => commit.commit_type = ConventionalCommit::parse_commit_type(inner)?,
-                Rule::scope => commit.scope = ConventionalCommit::parse_scope(inner)?,
+                Rule::breaking_change_phrase => {
+                    commit.breaking_change = true;
+                    breaking_token = BREAKING_TOKENS.into_iter().find(|token| *token == inner.as_str());
+                }
+                Rule::commit_type => commit.commit_type = ConventionalCommit::parse_commit_type(inner, aliases)?,
+                Rule::scope => commit.scope = ConventionalCommit::parse_scope(inner, aliases)?,
                 Rule::subject => commit.subject = ConventionalCommit::parse_subject(inner)?,
                 Rule::section => {
                     let blocks: Vec<&str> = inner.as_str().split("\n\n").collect();
                     let footer = blocks.last().unwrap_or(&"").to_string();
-                    let body = blocks[..blocks.len().saturating_sub(1)].join("\n\n");
+                    let mut body = blocks[..blocks.len().saturating_sub(1)].join("\n\n");
+                    let breaking_token = breaking_token.take();
+                    // Keep the token on a breaking change paragraph that ended up in the body
+                    if let Some(token) = breaking_token.filter(|_| !body.is_empty()) {
+                        body = format!("{token}: {body}");
+                    }
                     if !body.is_empty() {
                         commit.body = Some(body);
                     }
+                    commit.footers = match breaking_token {
+                        Some(token) if blocks.len() == 1 => parse_footers(&format!("{token}: {footer}")),
+                        _ => parse_footers(&footer),
+                    };
                     if !footer.is_empty() {
                         commit.footer = Some(footer);
                     }
@@ -70,11 +164,11 @@ impl ConventionalCommit {
         prune_message(self.to_string())
     }
 
-    fn parse_commit_type(pair: pest::iterators::Pair<Rule>) -> Result<CommitType, ConventionalCommitError> {
+    fn parse_commit_type(pair: pest::iterators::Pair<Rule>, aliases: &CommitTypeAliases) -> Result<CommitType, ConventionalCommitError> {
         match pair.as_rule() == Rule::commit_type {
             false => Err(ConventionalCommitError::InvalidParse("commit_type".to_string())),
             true => {
-                let commit_type = CommitType::from(pair.as_str());
+                let commit_type = aliases.resolve(pair.as_str());
                 if commit_type == CommitType::Unknown {
                     return Err(ConventionalCommitError::InvalidCommitType(pair.as_str().to_string()));
                 }
@@ -83,8 +177,8 @@ impl ConventionalCommit {
         }
     }
 
-    fn parse_scope(pair: pest::iterators::Pair<Rule>) -> Result<Option<String>, ConventionalCommitError> {
-        let scope_as_commit_type = CommitType::from(pair.as_str());
+    fn parse_scope(pair: pest::iterators::Pair<Rule>, aliases: &CommitTypeAliases) -> Result<Option<String>, ConventionalCommitError> {
+        let scope_as_commit_type = aliases.resolve(pair.as_str());
         match scope_as_commit_type {
             CommitType::Custom(value) => Ok(Some(value)),
             CommitType::Unknown => Ok(Some(pair.as_str().to_string())),
@@ -100,8 +194,11 @@ impl ConventionalCommit {
         if commit.commit_type == CommitType::Unknown && commit.scope.is_none() && !commit.subject.is_empty() {
             commit.commit_type = CommitType::NonCompliant;
         }
-        if commit.breaking_change {
-            return;
+        let paragraphs = commit.body.iter().flat_map(|body| body.split("\n\n"));
+        for paragraph in paragraphs.chain(commit.footer.as_deref()) {
+            if BREAKING_TOKENS.iter().any(|token| paragraph.starts_with(token)) {
+                commit.breaking_change = true;
+            }
         }
         if let Some(footer) = &commit.footer {
             let rest = footer
@@ -109,24 +206,127 @@ impl ConventionalCommit {
                 .or_else(|| footer.strip_prefix("BREAKING-CHANGE:"));
             if let Some(rest) = rest {
                 commit.footer = Some(rest.trim_start().to_string());
-                commit.breaking_change = true;
-                return;
-            }
-            if footer.starts_with("BREAKING CHANGE") || footer.starts_with("BREAKING-CHANGE") {
-                commit.breaking_change = true;
-                return;
             }
         }
-        if let Some(body) = &commit.body {
-            commit.breaking_change = body
-                .split("\n\n")
-                .any(|p| p.starts_with("BREAKING CHANGE") || p.starts_with("BREAKING-CHANGE"));
+        // The footer paragraph is covered by `footers`, so only body paragraphs are read here
+        let body_descriptions = commit.body.iter().flat_map(|body| body.split("\n\n")).filter_map(|paragraph| {
+            BREAKING_TOKENS
+                .iter()
+                .find_map(|token| paragraph.strip_prefix(token)?.strip_prefix(':'))
+                .map(|description| description.trim().to_string())
+        });
+        let footer_descriptions = commit
+            .footers
+            .iter()
+            .filter(|(token, _)| BREAKING_TOKENS.contains(&token.as_str()))
+            .map(|(_, description)| description.clone());
+        commit.breaking_changes = body_descriptions
+            .chain(footer_descriptions)
+            .filter(|description| !description.is_empty())
+            .collect();
+        if !commit.breaking_changes.is_empty() {
+            commit.breaking_change = true;
         }
     }
 
     pub fn is_breaking(&self) -> bool {
         self.breaking_change
     }
+
+    /// The value of the first `<token>: <value>` trailer in the footer; tokens match case-insensitively
+    pub fn trailer(&self, token: &str) -> Option<&str> {
+        self.footers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(token))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// The bump requested by a `Semrel-Bump: none|patch|minor|major` trailer
+    pub fn bump_override(&self) -> Option<BumpRule> {
+        let value = self.trailer("Semrel-Bump")?;
+        match value.to_lowercase().as_str() {
+            "none" => Some(BumpRule::NoBump),
+            "patch" => Some(BumpRule::Patch),
+            "minor" => Some(BumpRule::Minor),
+            "major" => Some(BumpRule::Major),
+            _ => {
+                tracing::warn!("Ignoring invalid Semrel-Bump trailer: {value:?}");
+                None
+            }
+        }
+    }
+
+    /// The version requested by a `Release-As: x.y.z` trailer
+    pub fn release_as(&self) -> Option<SimpleVersion> {
+        let value = self.trailer("Release-As")?;
+        value
+            .parse()
+            .map_err(|why| tracing::warn!("Ignoring invalid Release-As trailer {value:?}: {why}"))
+            .ok()
+    }
+
+    /// True when a `Semrel-Skip: true` trailer excludes the commit from the release
+    pub fn is_skipped(&self) -> bool {
+        self.trailer("Semrel-Skip")
+            .is_some_and(|value| matches!(value.to_lowercase().as_str(), "true" | "yes" | "1"))
+    }
+
+    /// The commit id named by a `This reverts commit <sha>` line, as written by `git revert`
+    ///
+    /// Ids shorter than 7 characters are ignored so words such as `add` are not read as ids.
+    pub fn reverted_id(&self) -> Option<&str> {
+        let sections = [self.body.as_deref(), self.footer.as_deref()];
+        sections
+            .into_iter()
+            .flatten()
+            .flat_map(|section| section.lines())
+            .filter_map(|line| line.trim().strip_prefix("This reverts commit "))
+            .map(|rest| rest.trim_end_matches(['.', ',']).trim())
+            .find(|sha| sha.len() >= MIN_REVERTED_ID_LENGTH && sha.chars().all(|c| c.is_ascii_hexdigit()))
+    }
+
+    /// The header of the reverted commit, from `revert: <header>` or git's `Revert "<header>"`
+    pub fn reverted_header(&self) -> Option<&str> {
+        match self.commit_type {
+            CommitType::Revert => Some(self.subject.trim()),
+            CommitType::NonCompliant => self.subject.strip_prefix("Revert \"").and_then(|rest| rest.strip_suffix('"')),
+            _ => None,
+        }
+    }
+
+    /// Extracts the conventional commits listed in the body of a squash merge
+    ///
+    /// Squash merges typically have a non-compliant subject such as `Feature/login (#42)` and
+    /// a body with one `* <message>` bullet per squashed commit.  Only bullets that parse to a
+    /// conventional commit, including custom types, are returned; a compliant subject is left alone.
+    pub fn squashed_commits(&self, options: &ParseOptions) -> Vec<ConventionalCommit> {
+        if self.commit_type != CommitType::NonCompliant {
+            return vec![];
+        }
+        let sections = [self.body.as_deref(), self.footer.as_deref()];
+        sections
+            .into_iter()
+            .flatten()
+            .flat_map(|section| section.lines())
+            .filter_map(|line| line.trim().strip_prefix("* "))
+            .filter_map(|line| ConventionalCommit::parse_with(line, options).ok())
+            .filter(|commit| !matches!(commit.commit_type, CommitType::NonCompliant | CommitType::Unknown))
+            .collect()
+    }
+}
+
+/// The text the grammar runs on: the message without git headers, with each line trimmed
+///
+/// Parse error positions refer to this text.
+pub(crate) fn parsed_text(message: &str) -> String {
+    let pruned = prune_message(message);
+    match pruned.is_empty() {
+        true => {
+            tracing::trace!("Pruned message empty.  Using original commit message: {message:?}");
+            message.to_owned()
+        }
+        false => pruned,
+    }
 }
 
 impl<'a> TryFrom<Commit<'a>> for ConventionalCommit {
@@ -161,8 +361,17 @@ impl fmt::Display for ConventionalCommit {
             string = format!("{string}\n\n{body}");
         }
 
+        // The breaking change token is stripped from `footer` when parsing, so put it back
+        let breaking_token = self
+            .footers
+            .first()
+            .map(|(token, _)| token.as_str())
+            .filter(|token| BREAKING_TOKENS.contains(token));
         if let Some(footer) = &self.footer {
-            string = format!("{string}\n\n{footer}");
+            string = match breaking_token {
+                Some(token) if !footer.starts_with(token) => format!("{string}\n\n{token}: {footer}"),
+                _ => format!("{string}\n\n{footer}"),
+            };
         } else if self.breaking_change && title.is_empty() {
             string = format!("{string}\n\nBREAKING CHANGE");
         }
@@ -197,6 +406,7 @@ impl TryFrom<&Path> for ConventionalCommit {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{CommitPattern, Gitmoji};
     use rstest::{fixture, rstest};
 
     #[fixture]
@@ -343,6 +553,182 @@ mod tests {
         );
     }
 
+    #[rstest]
+    #[case::git_revert("Revert \"feat: add login\"\n\nThis reverts commit 1a2b3c4d5e6f.", Some("1a2b3c4d5e6f"), Some("feat: add login"))]
+    #[case::conventional_revert("revert: feat: add login", None, Some("feat: add login"))]
+    #[case::conventional_revert_with_body("revert: add login\n\nThis reverts commit abc1234.", Some("abc1234"), Some("add login"))]
+    #[case::short_id("revert: add login\n\nThis reverts commit abc123.", None, Some("add login"))]
+    #[case::hex_word("revert: add login\n\nThis reverts commit add.", None, Some("add login"))]
+    #[case::not_a_revert("feat: add login", None, None)]
+    #[case::prose("fix: handle reverts\n\nThis reverts commit behaviour.", None, None)]
+    fn test_reverts(#[case] commit_message: &str, #[case] id: Option<&str>, #[case] header: Option<&str>) {
+        let commit = ConventionalCommit::new(commit_message).unwrap();
+        assert_eq!(commit.reverted_id(), id, "{commit:#?}");
+        assert_eq!(commit.reverted_header(), header, "{commit:#?}");
+    }
+
+    #[rstest]
+    #[case::github_squash("Feature/login (#42)\n\n* feat: add login\n\n* fix: handle timeout", vec!["feat: add login", "fix: handle timeout"])]
+    #[case::breaking_bullet("Feature/api (#43)\n\n* feat!: drop v1 endpoints\n* docs: update api docs", vec!["feat!: drop v1 endpoints", "docs: update api docs"])]
+    #[case::ignores_prose("Feature/login (#42)\n\n* feat: add login\n* tidied up some things\n- fix: not a squash bullet", vec!["feat: add login"])]
+    #[case::custom_type("Chore/tls (#44)\n\n* security: pin tls\n* fix: handle timeout", vec!["security: pin tls", "fix: handle timeout"])]
+    #[case::compliant_subject(squashed_feature_branch_commit(), vec![])]
+    #[case::no_body("Merge pull request #12 from user/login", vec![])]
+    fn test_squashed_commits(#[case] commit_message: impl AsRef<str>, #[case] expected: Vec<&str>) {
+        let commit = ConventionalCommit::new(commit_message).unwrap();
+        let squashed = commit
+            .squashed_commits(&ParseOptions::default())
+            .iter()
+            .map(|c| c.message())
+            .collect::<Vec<_>>();
+        assert_eq!(squashed, expected);
+    }
+
+    #[rstest]
+    #[case::none("fix: tidy logging\n\nSemrel-Bump: none", Some(BumpRule::NoBump), None, false)]
+    #[case::major("docs: rewrite guide\n\nSemrel-Bump: MAJOR", Some(BumpRule::Major), None, false)]
+    #[case::with_body("feat: add login\n\nSome details\n\nReviewed-by: someone\nsemrel-bump: patch", Some(BumpRule::Patch), None, false)]
+    #[case::invalid_bump("feat: add login\n\nSemrel-Bump: huge", None, None, false)]
+    #[case::release_as("chore: prepare release\n\nRelease-As: 3.0.0", None, Some(SimpleVersion::new(3, 0, 0)), false)]
+    #[case::invalid_release_as("chore: prepare release\n\nRelease-As: soon", None, None, false)]
+    #[case::skip("feat: experimental flag\n\nSemrel-Skip: true", None, None, true)]
+    #[case::skip_false("feat: experimental flag\n\nSemrel-Skip: false", None, None, false)]
+    #[case::body_only("feat: add login\n\nSemrel-Bump: none is mentioned here\n\nRefs: #12", None, None, false)]
+    fn test_trailers(#[case] commit_message: &str, #[case] bump: Option<BumpRule>, #[case] release_as: Option<SimpleVersion>, #[case] skipped: bool) {
+        let commit = ConventionalCommit::new(commit_message).unwrap();
+        assert_eq!(commit.bump_override(), bump, "{commit:#?}");
+        assert_eq!(commit.release_as(), release_as, "{commit:#?}");
+        assert_eq!(commit.is_skipped(), skipped, "{commit:#?}");
+    }
+
+    #[rstest]
+    #[case::none("feat: add login", vec![])]
+    #[case::prose("feat: add login\n\nSome closing thoughts", vec![])]
+    #[case::refs("fix: handle timeout\n\nRefs: #12", vec![("Refs", "#12")])]
+    #[case::hash_separator("fix: handle timeout\n\nCloses #4", vec![("Closes", "#4")])]
+    #[case::ordered(
+        "feat: add login\n\nSome details\n\nReviewed-by: Jane Doe\nRefs: #12\nCloses #4",
+        vec![("Reviewed-by", "Jane Doe"), ("Refs", "#12"), ("Closes", "#4")]
+    )]
+    #[case::multi_line("feat: add login\n\nCo-authored-by: Jane\nNote: first line\nsecond line", vec![("Co-authored-by", "Jane"), ("Note", "first line\nsecond line")])]
+    #[case::breaking("feat: drop v1\n\nBREAKING CHANGE: v1 endpoints are gone\nRefs: #7", vec![("BREAKING CHANGE", "v1 endpoints are gone"), ("Refs", "#7")])]
+    #[case::breaking_after_body("feat: drop v1\n\nDetails\n\nBREAKING-CHANGE: v1 is gone", vec![("BREAKING-CHANGE", "v1 is gone")])]
+    #[case::prose_with_colon("fix: typo\n\nSee the docs: they explain it", vec![])]
+    fn test_footers(#[case] commit_message: &str, #[case] expected: Vec<(&str, &str)>) {
+        let commit = ConventionalCommit::new(commit_message).unwrap();
+        let footers = commit
+            .footers
+            .iter()
+            .map(|(token, value)| (token.as_str(), value.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(footers, expected, "{commit:#?}");
+    }
+
+    #[rstest]
+    #[case::ticket("ENG-1234: handle timeout", CommitType::Fix, Some("ENG-1234"), "handle timeout")]
+    #[case::bracketed_ticket("[ENG-1234] handle timeout", CommitType::Fix, Some("ENG-1234"), "handle timeout")]
+    #[case::bracketed_type("[FEAT] add login", CommitType::Feat, None, "add login")]
+    #[case::whole_header("Added login", CommitType::Feat, None, "Added login")]
+    #[case::conventional("docs: readme", CommitType::Docs, None, "readme")]
+    #[case::rewrite_does_not_parse("feat - add login", CommitType::NonCompliant, None, "feat - add login")]
+    #[case::catch_all("updated readme", CommitType::Chore, None, "updated readme")]
+    #[case::catch_all_leaves_conventional("feat: add login", CommitType::Feat, None, "add login")]
+    fn test_patterns(#[case] commit_message: &str, #[case] commit_type: CommitType, #[case] scope: Option<&str>, #[case] subject: &str) {
+        let pattern = |regex: &str, commit_type: Option<&str>| CommitPattern::new(regex, commit_type.map(String::from), None).unwrap();
+        let options = ParseOptions {
+            patterns: vec![
+                pattern(r"^(?P<scope>[A-Z]+-\d+): (?P<subject>.+)$", Some("fix")).with_before_grammar(true),
+                pattern(r"^\[(?P<scope>[A-Z]+-\d+)\] (?P<subject>.+)$", Some("fix")),
+                pattern(r"^\[(?P<type>\w+)\] (?P<subject>.+)$", None),
+                pattern(r"^Add(ed)? ", Some("feat")),
+                pattern(r"^(?P<scope>\w+) - (?P<subject>.+)$", Some("fix")),
+                pattern(r"^(?P<subject>.+)$", Some("chore")),
+            ],
+            ..Default::default()
+        };
+        let commit = ConventionalCommit::parse_with(commit_message, &options).unwrap();
+        assert_eq!(
+            (commit.commit_type.clone(), commit.scope.as_deref(), commit.subject.as_str()),
+            (commit_type, scope, subject),
+            "{commit:#?}"
+        );
+    }
+
+    #[test]
+    fn patterns_leave_custom_types_to_the_grammar() {
+        let options = ParseOptions {
+            patterns: vec![CommitPattern::new(r"^(?P<scope>[A-Z]+-\d+): (?P<subject>.+)$", Some("fix".to_string()), None).unwrap()],
+            ..Default::default()
+        };
+        let commit = ConventionalCommit::parse_with("ENG-1234: handle timeout", &options).unwrap();
+        assert_eq!(commit.commit_type, CommitType::Custom("ENG-1234".to_string()));
+    }
+
+    #[test]
+    fn patterns_keep_the_footers() {
+        let options = ParseOptions {
+            patterns: vec![CommitPattern::new(r"^\[(?P<type>\w+)\] (?P<subject>.+)$", None, None).unwrap()],
+            ..Default::default()
+        };
+        let commit = ConventionalCommit::parse_with("[FEAT] add login\n\nRefs: #12", &options).unwrap();
+        assert_eq!(commit.trailer("Refs"), Some("#12"));
+    }
+
+    #[rstest]
+    #[case::shortcode(":sparkles: add login", CommitType::Feat, None, false)]
+    #[case::emoji("\u{1f41b} (api): handle timeout\n\nRefs: #12", CommitType::Fix, Some("api"), false)]
+    #[case::breaking(":boom: drop v1", CommitType::Feat, None, true)]
+    #[case::conventional("docs: readme", CommitType::Docs, None, false)]
+    #[case::unknown(":tada: first commit", CommitType::NonCompliant, None, false)]
+    fn test_gitmoji(#[case] commit_message: &str, #[case] commit_type: CommitType, #[case] scope: Option<&str>, #[case] breaking: bool) {
+        let options = ParseOptions {
+            gitmoji: Some(Gitmoji::default()),
+            ..Default::default()
+        };
+        let commit = ConventionalCommit::parse_with(commit_message, &options).unwrap();
+        assert_eq!(
+            (commit.commit_type.clone(), commit.scope.as_deref(), commit.is_breaking()),
+            (commit_type, scope, breaking),
+            "{commit:#?}"
+        );
+    }
+
+    #[rstest]
+    #[case::shortcode(":sparkles: add login")]
+    #[case::emoji("\u{1f41b} (api): handle timeout")]
+    #[case::breaking(":boom: drop v1")]
+    fn gitmoji_is_off_by_default(#[case] commit_message: &str) {
+        assert_eq!(ConventionalCommit::new(commit_message).unwrap().commit_type, CommitType::NonCompliant);
+    }
+
+    #[rstest]
+    #[case::none("feat: add login", vec![])]
+    #[case::shorthand("feat!: drop v1", vec![])]
+    #[case::footer("feat: drop v1\n\nBREAKING CHANGE: v1 endpoints are gone", vec!["v1 endpoints are gone"])]
+    #[case::several("feat: drop v1\n\nDetails\n\nBREAKING CHANGE: v1 is gone\nRefs: #7\nBREAKING-CHANGE: tokens expire", vec!["v1 is gone", "tokens expire"])]
+    #[case::multi_line("feat: drop v1\n\nBREAKING CHANGE: v1 is gone\nuse v2 instead", vec!["v1 is gone\nuse v2 instead"])]
+    #[case::in_body("feat: drop v1\n\nBREAKING CHANGE: v1 is gone\n\nRefs: #7", vec!["v1 is gone"])]
+    #[case::body_and_footer("feat: drop v1\n\nBREAKING CHANGE: v1 is gone\n\nMore details\n\nBREAKING-CHANGE: tokens expire", vec!["v1 is gone", "tokens expire"])]
+    fn test_breaking_changes(#[case] commit_message: &str, #[case] expected: Vec<&str>) {
+        let commit = ConventionalCommit::new(commit_message).unwrap();
+        assert_eq!(commit.breaking_changes, expected, "{commit:#?}");
+        assert_eq!(commit.is_breaking(), !expected.is_empty() || commit_message.starts_with("feat!"));
+    }
+
+    #[rstest]
+    #[case::plain("feat(auth): add login")]
+    #[case::body_and_footers("fix: handle timeout\n\nRetry twice.\n\nReviewed-by: Jane\nRefs: #12")]
+    #[case::breaking_footer("feat: drop v1\n\nBREAKING CHANGE: v1 endpoints are gone")]
+    #[case::breaking_footer_after_body("feat: drop v1\n\nDetails\n\nBREAKING-CHANGE: v1 is gone\nRefs: #7")]
+    #[case::breaking_shorthand("feat!: drop v1\n\nCloses #4")]
+    #[case::non_compliant("added login\n\nRefs: #12")]
+    #[case::breaking_paragraph_before_footer("feat: drop v1\n\nBREAKING CHANGE: v1 is gone\n\nRefs: #7")]
+    fn test_display_round_trips(#[case] commit_message: &str) {
+        let commit = ConventionalCommit::new(commit_message).unwrap();
+        let reparsed = ConventionalCommit::new(commit.to_string()).unwrap();
+        assert_eq!(reparsed, commit, "Display gave {:?}", commit.to_string());
+    }
+
     #[rstest]
     #[case::empty("", ConventionalCommitError::EmptyCommitMessage)]
     fn test_commit_parser_unhappy_paths(#[case] commit_message: impl AsRef<str>, #[case] expected: ConventionalCommitError) {
@@ -1,5 +1,7 @@
 use std::fmt;
 
+use crate::BumpRule;
+
 /// A commit message that follows the conventional commit standard
 #[derive(Debug, Default, PartialEq, Eq, Clone, Hash, PartialOrd, Ord)]
 pub enum CommitType {
@@ -73,6 +75,29 @@ impl CommitType {
         }
     }
 
+    /// Where the type's section appears in release notes; lower comes first
+    ///
+    /// Standard types are spaced by 10 so configured types can be placed between them.
+    pub fn order(&self) -> i64 {
+        match self {
+            CommitType::Feat => 10,
+            CommitType::Fix => 20,
+            CommitType::Perf => 30,
+            CommitType::Refactor => 40,
+            CommitType::Revert => 50,
+            CommitType::Style => 60,
+            CommitType::Test => 70,
+            CommitType::Build => 80,
+            CommitType::Chore => 90,
+            CommitType::Ci => 100,
+            CommitType::Cd => 110,
+            CommitType::Docs => 120,
+            CommitType::Custom(_) => 1000,
+            CommitType::NonCompliant => 2000,
+            CommitType::Unknown => 3000,
+        }
+    }
+
     /// Returns the commit type as a string
     pub fn as_str(&self) -> &str {
         match self {
@@ -135,22 +160,98 @@ impl From<&str> for CommitType {
         if s.is_empty() {
             return CommitType::Unknown;
         }
-        match s.to_lowercase().as_str() {
+        let lowered = s.to_lowercase();
+        let alias = BUILTIN_ALIASES
+            .iter()
+            .find(|(alias, _)| *alias == lowered)
+            .map(|(_, commit_type)| commit_type.clone());
+        match lowered.as_str() {
             "build" => CommitType::Build,
             "chore" => CommitType::Chore,
-            "ci" | "continuous integration" => CommitType::Ci,
-            "cd" | "deploy" => CommitType::Cd,
-            "doc" | "docs" | "documentation" => CommitType::Docs,
-            "feat" | "feature" | "features" => CommitType::Feat,
-            "fix" | "fixes" => CommitType::Fix,
-            "perf" | "performance" => CommitType::Perf,
+            "ci" => CommitType::Ci,
+            "cd" => CommitType::Cd,
+            "docs" => CommitType::Docs,
+            "feat" => CommitType::Feat,
+            "fix" => CommitType::Fix,
+            "perf" => CommitType::Perf,
             "refactor" => CommitType::Refactor,
             "revert" => CommitType::Revert,
             "style" => CommitType::Style,
-            "test" | "tests" => CommitType::Test,
-            _ => CommitType::Custom(s.to_string()),
+            "test" => CommitType::Test,
+            _ => alias.unwrap_or_else(|| CommitType::Custom(s.to_string())),
+        }
+    }
+}
+
+/// Alternative spellings accepted for the standard commit types
+pub const BUILTIN_ALIASES: [(&str, CommitType); 9] = [
+    ("continuous integration", CommitType::Ci),
+    ("deploy", CommitType::Cd),
+    ("doc", CommitType::Docs),
+    ("documentation", CommitType::Docs),
+    ("feature", CommitType::Feat),
+    ("features", CommitType::Feat),
+    ("fixes", CommitType::Fix),
+    ("performance", CommitType::Perf),
+    ("tests", CommitType::Test),
+];
+
+/// A commit type defined in the configuration, with how it is bumped and shown in release notes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeDefinition {
+    pub commit_type: CommitType,
+    /// The release notes heading; defaults to [`CommitType::as_release_note`]
+    pub title: Option<String>,
+    /// The bump used when no rule names the type
+    pub bump: Option<BumpRule>,
+    /// Replaces [`CommitType::order`]
+    pub order: Option<i64>,
+    /// Leaves the type out of the release notes; it still counts towards the bump
+    pub hidden: bool,
+}
+
+impl TypeDefinition {
+    pub fn new(commit_type: CommitType) -> Self {
+        Self {
+            commit_type,
+            title: None,
+            bump: None,
+            order: None,
+            hidden: false,
+        }
+    }
+}
+
+/// Commit type aliases added or disabled by the configuration, e.g. `bugfix` for `fix`
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct CommitTypeAliases {
+    aliases: Vec<(String, CommitType)>,
+    disabled: Vec<String>,
+}
+
+impl CommitTypeAliases {
+    /// Adds `aliases` and turns off the [`BUILTIN_ALIASES`] named in `disabled`; names ignore case
+    pub fn new(aliases: impl IntoIterator<Item = (String, CommitType)>, disabled: impl IntoIterator<Item = String>) -> Self {
+        Self {
+            aliases: aliases
+                .into_iter()
+                .map(|(alias, commit_type)| (alias.to_lowercase(), commit_type))
+                .collect(),
+            disabled: disabled.into_iter().map(|alias| alias.to_lowercase()).collect(),
         }
     }
+
+    /// Parses a commit type, applying the configured aliases before the built-in ones
+    pub fn resolve(&self, value: &str) -> CommitType {
+        let lowered = value.to_lowercase();
+        if let Some((_, commit_type)) = self.aliases.iter().find(|(alias, _)| *alias == lowered) {
+            return commit_type.clone();
+        }
+        if self.disabled.contains(&lowered) {
+            return CommitType::Custom(value.to_string());
+        }
+        CommitType::from(value)
+    }
 }
 
 impl PartialEq<&str> for CommitType {
@@ -215,4 +316,26 @@ mod tests {
         let actual = CommitType::from(input.as_ref());
         assert_eq!(actual, expected);
     }
+
+    #[rstest]
+    #[case::builtin("feat", CommitType::Feat)]
+    #[case::builtin_alias("feature", CommitType::Feat)]
+    #[case::added("bugfix", CommitType::Fix)]
+    #[case::added_ignore_case("HotFix", CommitType::Fix)]
+    #[case::added_custom_target("enhancement", CommitType::Custom("ENG".to_string()))]
+    #[case::disabled("deploy", CommitType::Custom("deploy".to_string()))]
+    #[case::overridden("features", CommitType::Docs)]
+    #[case::custom("ENG-2345", CommitType::Custom("ENG-2345".to_string()))]
+    fn test_resolve_aliases(#[case] input: &str, #[case] expected: CommitType) {
+        let aliases = CommitTypeAliases::new(
+            [
+                ("bugfix".to_string(), CommitType::Fix),
+                ("hotfix".to_string(), CommitType::Fix),
+                ("enhancement".to_string(), CommitType::Custom("ENG".to_string())),
+                ("features".to_string(), CommitType::Docs),
+            ],
+            ["Deploy".to_string()],
+        );
+        assert_eq!(aliases.resolve(input), expected);
+    }
 }
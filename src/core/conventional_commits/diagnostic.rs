@@ -0,0 +1,199 @@
+use std::fmt;
+
+use pest::error::{Error as PestError, ErrorVariant, LineColLocation};
+
+use super::Rule;
+use crate::{ConventionalCommitError, LintViolation};
+
+/// Where and why a commit message failed to parse
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct ParseDiagnostic {
+    /// 1-based line of the offending character
+    pub line: usize,
+    /// 1-based column of the offending character, in characters
+    pub column: usize,
+    /// The grammar rules that would have been accepted at this position
+    pub expected: Vec<String>,
+    pub message: String,
+    pub suggestion: Option<String>,
+    /// The full text of the offending line
+    pub source_line: String,
+}
+
+fn rule_name(rule: &Rule) -> String {
+    match rule {
+        Rule::commit_type => "commit type".to_string(),
+        Rule::scope => "scope".to_string(),
+        Rule::subject => "subject".to_string(),
+        Rule::section => "body or footer".to_string(),
+        Rule::breaking_change_shorthand => "`!`".to_string(),
+        Rule::breaking_change_phrase => "`BREAKING CHANGE`".to_string(),
+        other => format!("{other:?}"),
+    }
+}
+
+fn suggest(expected: &[Rule]) -> Option<String> {
+    let suggestion = match expected.first()? {
+        Rule::commit_type => "start the message with a type, e.g. `feat: add login`",
+        Rule::scope => "put a scope between the parentheses, e.g. `feat(auth): add login`",
+        Rule::subject => "describe the change after the colon, e.g. `feat: add login`",
+        Rule::section => "separate the body from the subject with a blank line",
+        _ => return None,
+    };
+    Some(suggestion.to_string())
+}
+
+impl ParseDiagnostic {
+    pub fn new(message: impl Into<String>, source: &str, line: usize, column: usize) -> Self {
+        Self {
+            line,
+            column,
+            expected: vec![],
+            message: message.into(),
+            suggestion: None,
+            source_line: source.lines().nth(line.saturating_sub(1)).unwrap_or_default().to_string(),
+        }
+    }
+
+    pub fn with_suggestion(mut self, suggestion: impl Into<String>) -> Self {
+        self.suggestion = Some(suggestion.into());
+        self
+    }
+
+    /// Builds a diagnostic from a pest parse failure
+    pub fn from_pest(error: &PestError<Rule>) -> Self {
+        let (line, column) = match error.line_col {
+            LineColLocation::Pos(position) => position,
+            LineColLocation::Span(start, _) => start,
+        };
+        let (message, expected) = match &error.variant {
+            ErrorVariant::ParsingError { positives, .. } => {
+                let names = positives.iter().map(rule_name).collect::<Vec<_>>();
+                let message = match names.is_empty() {
+                    true => "unexpected input".to_string(),
+                    false => format!("expected {}", names.join(" or ")),
+                };
+                (message, positives.clone())
+            }
+            ErrorVariant::CustomError { message } => (message.clone(), vec![]),
+        };
+        Self {
+            line,
+            column,
+            expected: expected.iter().map(rule_name).collect(),
+            message,
+            suggestion: suggest(&expected),
+            source_line: error.line().trim_end_matches(['\r', '\n']).to_string(),
+        }
+    }
+
+    /// Renders the offending line with a caret under the offending character
+    ///
+    /// ```text
+    /// 1:6: `fix` is a commit type and cannot be used as a scope
+    ///   |
+    /// 1 | feat(fix): handle timeout
+    ///   |      ^
+    ///   = help: use `fix` as the type, or choose another scope
+    /// ```
+    pub fn render(&self) -> String {
+        let number = self.line.to_string();
+        let gutter = " ".repeat(number.len());
+        let padding = " ".repeat(self.column.saturating_sub(1));
+        let mut rendered = format!("{}:{}: {}\n{gutter} |\n{number} | {}\n{gutter} | {padding}^", self.line, self.column, self.message, self.source_line);
+        if let Some(suggestion) = &self.suggestion {
+            rendered = format!("{rendered}\n{gutter} = help: {suggestion}");
+        }
+        rendered
+    }
+}
+
+impl fmt::Display for ParseDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at line {}, column {}", self.message, self.line, self.column)
+    }
+}
+
+impl ConventionalCommitError {
+    /// Locates the error within `message`, the text that failed to parse
+    ///
+    /// Like grammar errors, positions refer to the message with git headers removed and each
+    /// line trimmed.
+    pub fn diagnostic(&self, message: &str) -> ParseDiagnostic {
+        let message = &super::commit::parsed_text(message);
+        match self {
+            ConventionalCommitError::InvalidCommitMessage(diagnostic) => diagnostic.clone(),
+            ConventionalCommitError::ScopeIsCommitType(scope) => {
+                let header = message.lines().next().unwrap_or_default();
+                let column = header
+                    .find(&format!("({scope})"))
+                    .map(|index| header[..index].chars().count() + 2)
+                    .unwrap_or(1);
+                ParseDiagnostic::new(format!("`{scope}` is a commit type and cannot be used as a scope"), message, 1, column)
+                    .with_suggestion(format!("use `{scope}` as the type, or choose another scope"))
+            }
+            ConventionalCommitError::InvalidCommitType(commit_type) => {
+                ParseDiagnostic::new(format!("invalid commit type `{commit_type}`"), message, 1, 1).with_suggestion("use a type such as `feat`, `fix` or `chore`")
+            }
+            ConventionalCommitError::EmptyCommitMessage => ParseDiagnostic::new("empty commit message", message, 1, 1).with_suggestion("write a subject such as `feat: add login`"),
+            other => ParseDiagnostic::new(other.to_string(), message, 1, 1),
+        }
+    }
+}
+
+impl LintViolation {
+    /// The location of a parse error within `message`; policy violations have none
+    pub fn diagnostic(&self, message: &str) -> Option<ParseDiagnostic> {
+        match self {
+            LintViolation::Parse(why) => Some(why.diagnostic(message)),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CommitMessageParser;
+    use pest::Parser;
+
+    #[test]
+    fn from_pest_carries_span_and_expected_rule() {
+        let error = CommitMessageParser::parse(Rule::commit_type, "(auth): add login").unwrap_err();
+        let diagnostic = ParseDiagnostic::from_pest(&error);
+
+        assert_eq!((diagnostic.line, diagnostic.column), (1, 1));
+        assert_eq!(diagnostic.expected, vec!["commit type"]);
+        assert_eq!(diagnostic.message, "expected commit type");
+        assert_eq!(diagnostic.source_line, "(auth): add login");
+        assert!(diagnostic.suggestion.is_some());
+    }
+
+    #[test]
+    fn scope_error_points_at_the_scope() {
+        let message = "feat(fix): handle timeout";
+        let error = crate::ConventionalCommit::new(message).unwrap_err();
+        let diagnostic = error.diagnostic(message);
+
+        assert_eq!((diagnostic.line, diagnostic.column), (1, 6));
+        assert_eq!(diagnostic.suggestion.as_deref(), Some("use `fix` as the type, or choose another scope"));
+    }
+
+    #[test]
+    fn scope_error_uses_the_parsed_text() {
+        let message = "\n\n  feat(fix): handle timeout";
+        let error = crate::ConventionalCommit::new(message).unwrap_err();
+        let diagnostic = error.diagnostic(message);
+
+        assert_eq!((diagnostic.line, diagnostic.column), (1, 6));
+        assert_eq!(diagnostic.source_line, "feat(fix): handle timeout");
+    }
+
+    #[test]
+    fn render_points_at_the_column() {
+        let diagnostic =
+            ParseDiagnostic::new("`fix` is a commit type and cannot be used as a scope", "feat(fix): handle timeout", 1, 6).with_suggestion("use `fix` as the type, or choose another scope");
+        let expected = "1:6: `fix` is a commit type and cannot be used as a scope\n  |\n1 | feat(fix): handle timeout\n  |      ^\n  = help: use `fix` as the type, or choose another scope";
+        assert_eq!(diagnostic.render(), expected);
+    }
+}
@@ -0,0 +1,156 @@
+/// The gitmoji read as commit types by default, as `(shortcode, emoji, type)`
+///
+/// A type ending in `!` marks the commit as a breaking change.  Emoji are stored without the
+/// variation selector (U+FE0F) that some of them are written with.
+pub const DEFAULT_GITMOJI: [(&str, &str, &str); 26] = [
+    ("sparkles", "\u{2728}", "feat"),
+    ("boom", "\u{1f4a5}", "feat!"),
+    ("bug", "\u{1f41b}", "fix"),
+    ("ambulance", "\u{1f691}", "fix"),
+    ("adhesive_bandage", "\u{1fa79}", "fix"),
+    ("lock", "\u{1f512}", "fix"),
+    ("pencil2", "\u{270f}", "fix"),
+    ("zap", "\u{26a1}", "perf"),
+    ("recycle", "\u{267b}", "refactor"),
+    ("truck", "\u{1f69a}", "refactor"),
+    ("fire", "\u{1f525}", "refactor"),
+    ("rewind", "\u{23ea}", "revert"),
+    ("art", "\u{1f3a8}", "style"),
+    ("lipstick", "\u{1f484}", "style"),
+    ("white_check_mark", "\u{2705}", "test"),
+    ("test_tube", "\u{1f9ea}", "test"),
+    ("package", "\u{1f4e6}", "build"),
+    ("arrow_up", "\u{2b06}", "build"),
+    ("arrow_down", "\u{2b07}", "build"),
+    ("heavy_plus_sign", "\u{2795}", "build"),
+    ("heavy_minus_sign", "\u{2796}", "build"),
+    ("construction_worker", "\u{1f477}", "ci"),
+    ("green_heart", "\u{1f49a}", "ci"),
+    ("rocket", "\u{1f680}", "cd"),
+    ("memo", "\u{1f4dd}", "docs"),
+    ("wrench", "\u{1f527}", "chore"),
+];
+
+const VARIATION_SELECTOR: char = '\u{fe0f}';
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct GitmojiEntry {
+    /// The shortcode without colons, e.g. `sparkles`
+    code: Option<String>,
+    emoji: Option<String>,
+    /// The commit type, with a trailing `!` for breaking changes
+    commit_type: String,
+}
+
+/// Reads gitmoji headers such as `:sparkles: add login` or `✨ (auth): add login` as conventional commits
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Gitmoji {
+    entries: Vec<GitmojiEntry>,
+}
+
+impl Default for Gitmoji {
+    fn default() -> Self {
+        let entries = DEFAULT_GITMOJI
+            .iter()
+            .map(|(code, emoji, commit_type)| GitmojiEntry {
+                code: Some(code.to_string()),
+                emoji: Some(emoji.to_string()),
+                commit_type: commit_type.to_string(),
+            })
+            .collect();
+        Self { entries }
+    }
+}
+
+impl Gitmoji {
+    /// The default table with `overrides` applied
+    ///
+    /// Each override maps a shortcode or an emoji to a commit type such as `feat` or `feat!`;
+    /// an empty type removes the entry.
+    pub fn with_overrides(overrides: impl IntoIterator<Item = (String, String)>) -> Self {
+        let mut gitmoji = Gitmoji::default();
+        for (code, commit_type) in overrides {
+            let code = code.trim_matches(':').trim_end_matches(VARIATION_SELECTOR).to_string();
+            gitmoji
+                .entries
+                .retain(|entry| entry.code.as_ref() != Some(&code) && entry.emoji.as_ref() != Some(&code));
+            if commit_type.is_empty() {
+                continue;
+            }
+            let is_shortcode = code.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '+'));
+            let (code, emoji) = match is_shortcode {
+                true => (Some(code), None),
+                false => (None, Some(code)),
+            };
+            gitmoji.entries.push(GitmojiEntry { code, emoji, commit_type });
+        }
+        gitmoji
+    }
+
+    /// Finds the entry that `header` starts with, returning it and the rest of the header
+    fn find<'a>(&self, header: &'a str) -> Option<(&GitmojiEntry, &'a str)> {
+        if let Some((code, rest)) = header.strip_prefix(':').and_then(|rest| rest.split_once(':')) {
+            if let Some(entry) = self.entries.iter().find(|entry| entry.code.as_deref() == Some(code)) {
+                return Some((entry, rest));
+            }
+        }
+        self.entries.iter().find_map(|entry| {
+            let rest = header.strip_prefix(entry.emoji.as_deref()?)?;
+            Some((entry, rest.trim_start_matches(VARIATION_SELECTOR)))
+        })
+    }
+
+    /// Rewrites a gitmoji header as a conventional header, e.g. `:bug: (api) handle timeout` as `fix(api): handle timeout`
+    pub fn rewrite(&self, header: &str) -> Option<String> {
+        let (entry, rest) = self.find(header.trim_start())?;
+        let rest = rest.trim_start();
+        let (scope, rest) = match rest.strip_prefix('(').and_then(|rest| rest.split_once(')')) {
+            Some((scope, rest)) => (format!("({})", scope.trim()), rest),
+            None => (String::new(), rest),
+        };
+        let subject = rest.trim_start().trim_start_matches(':').trim();
+        if subject.is_empty() {
+            return None;
+        }
+        let (commit_type, bang) = match entry.commit_type.strip_suffix('!') {
+            Some(commit_type) => (commit_type, "!"),
+            None => (entry.commit_type.as_str(), ""),
+        };
+        Some(format!("{commit_type}{scope}{bang}: {subject}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::shortcode(":sparkles: add login", Some("feat: add login"))]
+    #[case::emoji("\u{2728} add login", Some("feat: add login"))]
+    #[case::emoji_with_selector("\u{267b}\u{fe0f} extract helper", Some("refactor: extract helper"))]
+    #[case::scope(":bug: (api): handle timeout", Some("fix(api): handle timeout"))]
+    #[case::scope_without_colon("\u{1f41b} (api) handle timeout", Some("fix(api): handle timeout"))]
+    #[case::breaking(":boom: drop v1", Some("feat!: drop v1"))]
+    #[case::unknown(":tada: first commit", None)]
+    #[case::no_subject(":sparkles:", None)]
+    #[case::conventional("feat: add login", None)]
+    fn test_rewrite(#[case] header: &str, #[case] expected: Option<&str>) {
+        assert_eq!(Gitmoji::default().rewrite(header).as_deref(), expected);
+    }
+
+    #[test]
+    fn overrides_replace_add_and_remove_entries() {
+        let gitmoji = Gitmoji::with_overrides([
+            ("tada".to_string(), "feat".to_string()),
+            ("\u{1f680}".to_string(), "chore".to_string()),
+            ("memo".to_string(), String::new()),
+        ]);
+
+        assert_eq!(gitmoji.rewrite(":tada: first commit").as_deref(), Some("feat: first commit"));
+        assert_eq!(gitmoji.rewrite("\u{1f680} ship it").as_deref(), Some("chore: ship it"));
+        assert_eq!(gitmoji.rewrite(":memo: readme"), None);
+        assert_eq!(gitmoji.rewrite(":bug: typo").as_deref(), Some("fix: typo"));
+        assert_eq!(gitmoji.rewrite("tada first commit"), None);
+    }
+}
@@ -0,0 +1,230 @@
+use std::fmt;
+
+use crate::{CommitType, ConventionalCommit, LintViolation, ParseOptions, build_default_rules, prune_message};
+
+/// How the first letter of a subject must be written
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SubjectCase {
+    /// Either case
+    #[default]
+    Any,
+    /// `feat: add login`
+    Lower,
+    /// `feat: Add login`
+    Sentence,
+}
+
+impl SubjectCase {
+    fn matches(&self, subject: &str) -> bool {
+        let first = subject.chars().find(|c| c.is_alphabetic());
+        match (self, first) {
+            (SubjectCase::Any, _) | (_, None) => true,
+            (SubjectCase::Lower, Some(c)) => !c.is_uppercase(),
+            (SubjectCase::Sentence, Some(c)) => !c.is_lowercase(),
+        }
+    }
+}
+
+impl fmt::Display for SubjectCase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SubjectCase::Any => write!(f, "any"),
+            SubjectCase::Lower => write!(f, "lower"),
+            SubjectCase::Sentence => write!(f, "sentence"),
+        }
+    }
+}
+
+/// The checks applied by [`lint_message`] on top of the grammar
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LintPolicy {
+    /// Commit types that may be used
+    pub allowed_types: Vec<CommitType>,
+    /// Scopes that may be used; empty allows any scope
+    pub allowed_scopes: Vec<String>,
+    /// Every commit must have a scope
+    pub require_scope: bool,
+    /// The maximum length of the subject line, in characters
+    pub max_subject_length: usize,
+    /// The maximum length of each body and footer line, in characters
+    pub max_body_line_length: Option<usize>,
+    /// How the subject must start
+    pub subject_case: SubjectCase,
+    /// The subject line may end with a period
+    pub allow_trailing_period: bool,
+    /// Messages that are not conventional commits are rejected
+    pub forbid_non_compliant: bool,
+    /// Every message must reference an issue, e.g. `#12` or `ENG-1234`
+    pub require_issue_ref: bool,
+    /// How commit messages are read
+    pub parsing: ParseOptions,
+}
+
+impl Default for LintPolicy {
+    fn default() -> Self {
+        Self {
+            allowed_types: build_default_rules().map(|(commit_type, _)| commit_type).collect(),
+            allowed_scopes: vec![],
+            require_scope: false,
+            max_subject_length: 72,
+            max_body_line_length: None,
+            subject_case: SubjectCase::Any,
+            allow_trailing_period: false,
+            forbid_non_compliant: true,
+            require_issue_ref: false,
+            parsing: ParseOptions::default(),
+        }
+    }
+}
+
+/// True when `message` mentions an issue such as `#12`, `GH-12` or `ENG-1234`
+fn has_issue_ref(message: &str) -> bool {
+    message
+        .split(|c: char| c.is_whitespace() || matches!(c, '(' | ')' | '[' | ']' | ',' | ':' | ';'))
+        .any(|word| {
+            let word = word.trim_end_matches('.');
+            // `#12` and `owner/repo#12`
+            if let Some((_, number)) = word.rsplit_once('#') {
+                return !number.is_empty() && number.chars().all(|c| c.is_ascii_digit());
+            }
+            match word.split_once('-') {
+                Some((project, number)) => {
+                    project.chars().next().is_some_and(|c| c.is_ascii_uppercase())
+                        && project.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+                        && !number.is_empty()
+                        && number.chars().all(|c| c.is_ascii_digit())
+                }
+                None => false,
+            }
+        })
+}
+
+/// Removes the `#` comment lines git adds to a message being edited, and anything below the scissors line
+pub fn strip_comments(message: &str) -> String {
+    message
+        .lines()
+        .take_while(|line| !line.starts_with("# ------------------------ >8 ------------------------"))
+        .filter(|line| !line.starts_with('#'))
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_string()
+}
+
+/// Checks a commit message against the conventional commit grammar and `policy`
+///
+/// A message that does not parse only reports the parse error; otherwise every violation is reported.
+pub fn lint_message(message: &str, policy: &LintPolicy) -> Vec<LintViolation> {
+    let commit = match ConventionalCommit::parse_with(message, &policy.parsing) {
+        Ok(commit) => commit,
+        Err(why) => return vec![LintViolation::Parse(why)],
+    };
+    let mut violations = vec![];
+    match &commit.commit_type {
+        CommitType::NonCompliant | CommitType::Unknown => {
+            if policy.forbid_non_compliant {
+                violations.push(LintViolation::NonCompliant);
+            }
+        }
+        commit_type if !policy.allowed_types.contains(commit_type) => {
+            let allowed = policy
+                .allowed_types
+                .iter()
+                .map(|commit_type| commit_type.as_str())
+                .collect::<Vec<_>>()
+                .join(", ");
+            violations.push(LintViolation::UnknownType(commit_type.as_str().to_string(), allowed));
+        }
+        _ => match &commit.scope {
+            None if policy.require_scope => violations.push(LintViolation::MissingScope),
+            Some(scope) if !policy.allowed_scopes.is_empty() && !policy.allowed_scopes.contains(scope) => {
+                violations.push(LintViolation::ScopeNotAllowed(scope.clone(), policy.allowed_scopes.join(", ")));
+            }
+            _ => {}
+        },
+    }
+    if !policy.subject_case.matches(&commit.subject) {
+        violations.push(LintViolation::SubjectCase(policy.subject_case.to_string()));
+    }
+    let pruned = prune_message(message);
+    let subject_line = pruned.lines().next().unwrap_or_default();
+    let length = subject_line.chars().count();
+    if length > policy.max_subject_length {
+        violations.push(LintViolation::SubjectTooLong(length, policy.max_subject_length));
+    }
+    if !policy.allow_trailing_period && subject_line.trim_end().ends_with('.') {
+        violations.push(LintViolation::TrailingPeriod);
+    }
+    if let Some(max) = policy.max_body_line_length {
+        for (index, line) in pruned.lines().enumerate().skip(1) {
+            let length = line.chars().count();
+            if length > max {
+                violations.push(LintViolation::BodyLineTooLong(index + 1, length, max));
+            }
+        }
+    }
+    if policy.require_issue_ref && !has_issue_ref(&pruned) {
+        violations.push(LintViolation::MissingIssueRef);
+    }
+    violations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CommitTypeAliases, ConventionalCommitError};
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::valid("feat(auth): add login", LintPolicy::default(), vec![])]
+    #[case::valid_with_body("fix: handle timeout\n\nRetry twice before giving up.", LintPolicy::default(), vec![])]
+    #[case::empty("", LintPolicy::default(), vec![LintViolation::Parse(ConventionalCommitError::EmptyCommitMessage)])]
+    #[case::non_compliant("added login", LintPolicy::default(), vec![LintViolation::NonCompliant])]
+    #[case::unknown_type(
+        "feature-x: add login",
+        LintPolicy { allowed_types: vec![CommitType::Feat, CommitType::Fix], ..LintPolicy::default() },
+        vec![LintViolation::UnknownType("feature-x".to_string(), "feat, fix".to_string())]
+    )]
+    #[case::missing_scope("feat: add login", LintPolicy { require_scope: true, ..LintPolicy::default() }, vec![LintViolation::MissingScope])]
+    #[case::scoped("feat(auth): add login", LintPolicy { require_scope: true, ..LintPolicy::default() }, vec![])]
+    #[case::too_long("feat: add a login form that remembers the user", LintPolicy { max_subject_length: 20, ..LintPolicy::default() }, vec![LintViolation::SubjectTooLong(46, 20)])]
+    #[case::trailing_period("fix: handle timeout.", LintPolicy::default(), vec![LintViolation::TrailingPeriod])]
+    #[case::trailing_period_allowed("fix: handle timeout.", LintPolicy { allow_trailing_period: true, ..LintPolicy::default() }, vec![])]
+    #[case::several("Added login.", LintPolicy::default(), vec![LintViolation::NonCompliant, LintViolation::TrailingPeriod])]
+    #[case::non_compliant_allowed("added login", LintPolicy { forbid_non_compliant: false, ..LintPolicy::default() }, vec![])]
+    #[case::scope_allowed("feat(api): add login", LintPolicy { allowed_scopes: vec!["api".to_string()], ..LintPolicy::default() }, vec![])]
+    #[case::scope_not_allowed(
+        "feat(auth): add login",
+        LintPolicy { allowed_scopes: vec!["api".to_string(), "cli".to_string()], ..LintPolicy::default() },
+        vec![LintViolation::ScopeNotAllowed("auth".to_string(), "api, cli".to_string())]
+    )]
+    #[case::lower_case("feat: Add login", LintPolicy { subject_case: SubjectCase::Lower, ..LintPolicy::default() }, vec![LintViolation::SubjectCase("lower".to_string())])]
+    #[case::sentence_case("feat: add login", LintPolicy { subject_case: SubjectCase::Sentence, ..LintPolicy::default() }, vec![LintViolation::SubjectCase("sentence".to_string())])]
+    #[case::sentence_case_ok("feat: Add login", LintPolicy { subject_case: SubjectCase::Sentence, ..LintPolicy::default() }, vec![])]
+    #[case::body_line_too_long(
+        "feat: add login\n\nshort line\nthis line is far too long",
+        LintPolicy { max_body_line_length: Some(12), ..LintPolicy::default() },
+        vec![LintViolation::BodyLineTooLong(4, 25, 12)]
+    )]
+    #[case::missing_issue_ref("feat: add login", LintPolicy { require_issue_ref: true, ..LintPolicy::default() }, vec![LintViolation::MissingIssueRef])]
+    #[case::issue_ref_hash("feat: add login (#12)", LintPolicy { require_issue_ref: true, ..LintPolicy::default() }, vec![])]
+    #[case::issue_ref_jira("feat: add login\n\nRefs: ENG-1234", LintPolicy { require_issue_ref: true, ..LintPolicy::default() }, vec![])]
+    #[case::alias(
+        "bugfix: handle timeout",
+        LintPolicy { allowed_types: vec![CommitType::Fix], parsing: ParseOptions { aliases: CommitTypeAliases::new([("bugfix".to_string(), CommitType::Fix)], []), ..Default::default() }, ..LintPolicy::default() },
+        vec![]
+    )]
+    #[case::issue_ref_cross_repo("fix: handle timeout\n\nCloses owner/repo#7", LintPolicy { require_issue_ref: true, ..LintPolicy::default() }, vec![])]
+    fn test_lint_message(#[case] message: &str, #[case] policy: LintPolicy, #[case] expected: Vec<LintViolation>) {
+        assert_eq!(lint_message(message, &policy), expected);
+    }
+
+    #[rstest]
+    #[case::comments("feat: add login\n# Please enter the commit message\n#\n", "feat: add login")]
+    #[case::scissors("feat: add login\n\n# ------------------------ >8 ------------------------\ndiff --git a/a b/a", "feat: add login")]
+    #[case::untouched("fix: handle #12", "fix: handle #12")]
+    fn test_strip_comments(#[case] message: &str, #[case] expected: &str) {
+        assert_eq!(strip_comments(message), expected);
+    }
+}
@@ -1,7 +1,17 @@
 mod commit;
 mod commit_parser;
 mod commit_type;
+mod diagnostic;
+mod gitmoji;
+mod lint;
+mod parse_options;
+mod pattern;
 
 pub use commit::ConventionalCommit;
 pub use commit_parser::{CommitMessageParser, Rule};
-pub use commit_type::CommitType;
+pub use commit_type::{BUILTIN_ALIASES, CommitType, CommitTypeAliases, TypeDefinition};
+pub use diagnostic::ParseDiagnostic;
+pub use gitmoji::{DEFAULT_GITMOJI, Gitmoji};
+pub use lint::{LintPolicy, SubjectCase, lint_message, strip_comments};
+pub use parse_options::ParseOptions;
+pub use pattern::CommitPattern;
@@ -0,0 +1,45 @@
+use super::{CommitPattern, CommitTypeAliases, Gitmoji};
+
+/// Configuration that changes how commit messages are read
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ParseOptions {
+    /// How commit types are spelled
+    pub aliases: CommitTypeAliases,
+    /// Tried in order on the header of messages that are not conventional commits, or on every
+    /// header for patterns marked [`CommitPattern::with_before_grammar`]
+    pub patterns: Vec<CommitPattern>,
+    /// Reads gitmoji headers when set; tried before the patterns
+    pub gitmoji: Option<Gitmoji>,
+}
+
+impl ParseOptions {
+    /// The message with its header rewritten by gitmoji or the first matching pattern
+    pub fn rewrite(&self, message: &str) -> Option<String> {
+        rewrite_header(message, |header| {
+            self.gitmoji
+                .as_ref()
+                .and_then(|gitmoji| gitmoji.rewrite(header))
+                .or_else(|| self.patterns.iter().find_map(|pattern| pattern.rewrite(header)))
+        })
+    }
+
+    /// The message with its header rewritten by the first matching pattern that runs before the grammar
+    pub fn rewrite_before_grammar(&self, message: &str) -> Option<String> {
+        rewrite_header(message, |header| {
+            self.patterns
+                .iter()
+                .filter(|pattern| pattern.is_before_grammar())
+                .find_map(|pattern| pattern.rewrite(header))
+        })
+    }
+}
+
+fn rewrite_header(message: &str, rewrite: impl FnOnce(&str) -> Option<String>) -> Option<String> {
+    let message = message.trim_start();
+    let (header, rest) = message.split_once('\n').unwrap_or((message, ""));
+    let header = rewrite(header)?;
+    match rest.is_empty() {
+        true => Some(header),
+        false => Some(format!("{header}\n{rest}")),
+    }
+}
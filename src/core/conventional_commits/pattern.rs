@@ -0,0 +1,82 @@
+use regex::Regex;
+
+/// A regular expression that reads a non-conventional header as a conventional commit
+///
+/// Named captures `type`, `scope` and `subject` supply those parts of the commit; a non-empty
+/// `breaking` capture marks it as a breaking change.  `commit_type` and `scope` are used when
+/// the expression has no such capture, and the whole header is the subject when it has no
+/// `subject` capture.
+#[derive(Debug, Clone)]
+pub struct CommitPattern {
+    regex: Regex,
+    commit_type: Option<String>,
+    scope: Option<String>,
+    before_grammar: bool,
+}
+
+impl PartialEq for CommitPattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.regex.as_str() == other.regex.as_str() && self.commit_type == other.commit_type && self.scope == other.scope && self.before_grammar == other.before_grammar
+    }
+}
+
+impl Eq for CommitPattern {}
+
+impl CommitPattern {
+    pub fn new(regex: &str, commit_type: Option<String>, scope: Option<String>) -> Result<Self, regex::Error> {
+        Ok(Self {
+            regex: Regex::new(regex)?,
+            commit_type,
+            scope,
+            before_grammar: false,
+        })
+    }
+
+    /// Tries the pattern before the conventional commit grammar, so it also reads headers the
+    /// grammar accepts, e.g. `ENG-1234: handle timeout`
+    pub fn with_before_grammar(mut self, before_grammar: bool) -> Self {
+        self.before_grammar = before_grammar;
+        self
+    }
+
+    pub fn is_before_grammar(&self) -> bool {
+        self.before_grammar
+    }
+
+    /// True when the expression can supply a commit type
+    pub fn has_type(&self) -> bool {
+        self.commit_type.is_some() || self.regex.capture_names().flatten().any(|name| name == "type")
+    }
+
+    /// Rewrites `header` as a conventional header such as `fix(ENG-1234): handle timeout`
+    pub fn rewrite(&self, header: &str) -> Option<String> {
+        let captures = self.regex.captures(header)?;
+        let capture = |name: &str| captures.name(name).map(|m| m.as_str().trim()).filter(|value| !value.is_empty());
+        let commit_type = capture("type").or(self.commit_type.as_deref())?;
+        let scope = match capture("scope").or(self.scope.as_deref()) {
+            Some(scope) => format!("({scope})"),
+            None => String::new(),
+        };
+        let bang = if capture("breaking").is_some() { "!" } else { "" };
+        let subject = capture("subject").unwrap_or(header.trim());
+        Some(format!("{commit_type}{scope}{bang}: {subject}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::ticket(r"^(?P<scope>[A-Z]+-\d+): (?P<subject>.+)$", Some("fix"), None, "ENG-1234: handle timeout", Some("fix(ENG-1234): handle timeout"))]
+    #[case::bracketed_type(r"^\[(?P<type>\w+)\] (?P<subject>.+)$", None, None, "[FIX] handle timeout", Some("FIX: handle timeout"))]
+    #[case::whole_header(r"^Add(ed)? ", Some("feat"), Some("legacy"), "Added login", Some("feat(legacy): Added login"))]
+    #[case::breaking(r"^\[(?P<type>\w+)(?P<breaking>!?)\] (?P<subject>.+)$", None, None, "[FEAT!] drop v1", Some("FEAT!: drop v1"))]
+    #[case::no_match(r"^\[(?P<type>\w+)\] (?P<subject>.+)$", None, None, "handle timeout", None)]
+    #[case::no_type(r"^(?P<subject>.+)$", None, None, "handle timeout", None)]
+    fn test_rewrite(#[case] regex: &str, #[case] commit_type: Option<&str>, #[case] scope: Option<&str>, #[case] header: &str, #[case] expected: Option<&str>) {
+        let pattern = CommitPattern::new(regex, commit_type.map(String::from), scope.map(String::from)).unwrap();
+        assert_eq!(pattern.rewrite(header).as_deref(), expected);
+    }
+}
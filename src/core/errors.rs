@@ -1,6 +1,6 @@
 use std::path::PathBuf;
 
-use super::VersionError;
+use super::{BumpRule, ParseDiagnostic, VersionError};
 
 #[derive(thiserror::Error, Debug, PartialEq, Eq)]
 pub enum ConfigError {
@@ -14,6 +14,18 @@ pub enum ConfigError {
     ConfigNotFound(PathBuf),
     #[error("Empty config: {0}")]
     EmptyConfig(PathBuf),
+    #[error("Invalid [semrel.lint] config: {0}")]
+    InvalidLintConfig(String),
+    #[error("Invalid [semrel.aliases] config: {0}")]
+    InvalidAliasConfig(String),
+    #[error("Invalid [semrel.types] config: {0}")]
+    InvalidTypeConfig(String),
+    #[error("Invalid [[semrel.patterns]] config: {0}")]
+    InvalidPatternConfig(String),
+    #[error("Invalid [semrel.gitmoji] config: {0}")]
+    InvalidGitmojiConfig(String),
+    #[error("Invalid [semrel.strict] config: {0}")]
+    InvalidStrictConfig(String),
 }
 
 #[derive(thiserror::Error, Debug, PartialEq, Eq)]
@@ -70,16 +82,52 @@ pub enum RepositoryError {
     InvalidConventionalCommit(#[from] ConventionalCommitError),
     #[error("Could not read file: {0}")]
     CouldNotReadFile(PathBuf),
+    #[error("Could not write file {0}: {1}")]
+    CouldNotWriteFile(PathBuf, String),
     #[error("Invalid commit: {0}")]
     InvalidCommit(String),
+    #[error("Failed to resolve revision: {0}. {1}")]
+    InvalidRevision(String, String),
+    #[error("Could not determine git identity: {0}.  Set user.name and user.email in your git config")]
+    NoSignature(String),
+    #[error("No release found at or before commit: {0}")]
+    ReleaseNotFound(String),
+    #[error("Tag already exists: {0}")]
+    TagExists(String),
+    #[error("Tag {0} already exists and points to {1}, not {2}")]
+    TagMismatch(String, String, String),
+    #[error("Failed to create tag {0}: {1}")]
+    TagError(String, String),
+    #[error(
+        "Repository is a shallow clone and the previous release is not within the {0} commits fetched.  Fetch more history (e.g. `git fetch --unshallow`, or `fetch-depth: 0` with actions/checkout)"
+    )]
+    ShallowRepository(usize),
+    #[error("Invalid path pattern {0}: {1}")]
+    InvalidPathPattern(String, String),
+    #[error("Strict mode: commits are not conventional commits or have a type without a bump rule:\n{0}")]
+    NonCompliantCommits(String),
+    #[error("Working tree has uncommitted changes: {0}")]
+    DirtyWorkingTree(String),
+    #[error("HEAD is detached; check out a release branch")]
+    DetachedHead,
+    #[error("Branch {0} is not a release branch (allowed: {1})")]
+    BranchNotAllowed(String, String),
+    #[error("HEAD is already tagged as release {0}")]
+    AlreadyReleased(String),
+    #[error("Failed to write note for {0}: {1}")]
+    NoteError(String, String),
+    #[error("Hook {0} was not installed by semrel.  Use --force to replace it")]
+    ForeignHook(PathBuf),
+    #[error("Failed to write hook {0}: {1}")]
+    HookError(PathBuf, String),
 }
 
-#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
 pub enum ConventionalCommitError {
     #[error("Invalid commit type: {0}")]
     InvalidCommitType(String),
     #[error("Invalid commit message: {0}")]
-    InvalidCommitMessage(String),
+    InvalidCommitMessage(ParseDiagnostic),
     #[error("Invalid scope: {0}.  Do not use a standard conventional commit type as a scope.")]
     ScopeIsCommitType(String),
     #[error("{0}")]
@@ -88,6 +136,34 @@ pub enum ConventionalCommitError {
     InvalidParse(String),
     #[error("Empty commit message")]
     EmptyCommitMessage,
+    #[error("Cannot set scope {0:?}: the message is not a conventional commit, so a commit type is needed as well")]
+    ScopeWithoutType(String),
+    #[error("Invalid Semrel-Bump: {0}.  Use none, patch, minor or major.")]
+    InvalidBumpOverride(BumpRule),
+}
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum LintViolation {
+    #[error("{0}")]
+    Parse(ConventionalCommitError),
+    #[error("Not a conventional commit: expected `<type>(<scope>): <subject>`")]
+    NonCompliant,
+    #[error("Unknown commit type: {0} (allowed: {1})")]
+    UnknownType(String, String),
+    #[error("Missing scope: expected `<type>(<scope>): <subject>`")]
+    MissingScope,
+    #[error("Scope {0} is not allowed (allowed: {1})")]
+    ScopeNotAllowed(String, String),
+    #[error("Subject must use {0} case")]
+    SubjectCase(String),
+    #[error("Subject line is {0} characters long; the limit is {1}")]
+    SubjectTooLong(usize, usize),
+    #[error("Subject line ends with a period")]
+    TrailingPeriod,
+    #[error("Line {0} is {1} characters long; the limit is {2}")]
+    BodyLineTooLong(usize, usize, usize),
+    #[error("Missing issue reference, e.g. `#12` or `ENG-1234`")]
+    MissingIssueRef,
 }
 
 #[derive(thiserror::Error, Debug, PartialEq, Eq)]
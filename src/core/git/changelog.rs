@@ -1,19 +1,39 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 
 use git2::{Oid, TreeWalkMode};
 
 use super::CommitInfo;
-use crate::{BumpRule, CommitType, ConventionalCommit, RepositoryError, SimpleVersion, SupportedManifest, find_top_of_repo};
+use super::filtering::{PathFilters, ProjectMatcher};
+use super::notes::{NOTES_REF, apply_note_override, read_note_override};
+use crate::{BumpRule, CommitType, ConventionalCommit, ParseOptions, RepositoryError, SimpleVersion, SupportedManifest, TypeDefinition, find_top_of_repo};
 
 /// Streaming commit collection that stops at the previous release boundary.
 /// Walks commits from HEAD backwards, stopping when it finds a commit that
 /// actually changed the manifest version to a value <= current_version.
 /// Commits that modify the manifest without changing the version field
-/// (e.g. dependency updates) are not treated as boundaries.
+/// (e.g. dependency updates) are not treated as boundaries.  Overrides stored
+/// in `refs/notes/semrel` are applied to commit messages before parsing.
 pub fn collect_changelog_commits_streaming(repo: &git2::Repository, manifest_path: &Path, relative_manifest_path: &Path, current_version: SimpleVersion) -> Result<Vec<CommitInfo>, RepositoryError> {
-    let mut collected_commits = Vec::new();
     let walker = revwalk(repo, manifest_path)?;
+    let (collected_commits, _) = collect_commits(repo, walker, relative_manifest_path, current_version, &ParseOptions::default())?;
+    Ok(collected_commits)
+}
+
+/// Collects commits from an already positioned walker until the previous release boundary
+///
+/// Also returns whether the boundary was found.  The walk stops without a boundary when
+/// history runs out, including at the graft point of a shallow clone, where the parent
+/// needed to tell whether the commit changed the version is missing.
+pub(super) fn collect_commits(
+    repo: &git2::Repository,
+    walker: impl IntoIterator<Item = Result<(Oid, Vec<PathBuf>), RepositoryError>>,
+    relative_manifest_path: &Path,
+    current_version: SimpleVersion,
+    parsing: &ParseOptions,
+) -> Result<(Vec<CommitInfo>, bool), RepositoryError> {
+    let grafts = grafted_commits(repo);
+    let mut collected_commits = Vec::new();
 
     for oid_result in walker {
         let (oid, files_changed) = match oid_result {
@@ -23,44 +43,152 @@ pub fn collect_changelog_commits_streaming(repo: &git2::Repository, manifest_pat
                 continue;
             }
         };
-        let commit = repo
-            .find_commit(oid)
-            .map_err(|_| RepositoryError::CommitNotFound(oid.to_string()))?;
-
-        let conventional_commit = ConventionalCommit::try_from(commit.message().unwrap_or_default())?;
-        let timestamp = commit.time().seconds();
-        let timestamp = timestamp.max(0) as u64;
-        let commit_info = CommitInfo::new(oid.to_string(), files_changed, conventional_commit, timestamp);
+        if grafts.contains(&oid) {
+            tracing::warn!("History is cut off at shallow graft {oid}; older commits are not collected");
+            return Ok((collected_commits, false));
+        }
+        let (commit, commit_info) = load_commit_info(repo, oid, files_changed, parsing)?;
 
         if commit_info.files.iter().any(|f| f == relative_manifest_path) {
-            let data = load_file_data(repo, &commit, relative_manifest_path)?;
-            let version = SupportedManifest::parse(relative_manifest_path, &data)?.version()?;
-            if version <= current_version {
-                let parent_version = commit.parents().next().and_then(|p| {
-                    let data = load_file_data(repo, &p, relative_manifest_path)
-                        .map_err(|why| tracing::debug!("Could not load parent manifest: {why}"))
-                        .ok()?;
-                    let manifest = SupportedManifest::parse(relative_manifest_path, &data)
-                        .map_err(|why| tracing::debug!("Could not parse parent manifest: {why}"))
-                        .ok()?;
-                    manifest
-                        .version()
-                        .map_err(|why| tracing::debug!("Could not read parent version: {why}"))
-                        .ok()
-                });
-                if parent_version.as_ref() != Some(&version) {
-                    tracing::debug!("Version changed to {} (parent: {:?}) - stopping", version, parent_version);
-                    break;
+            if let Some(version) = version_change(repo, &commit, relative_manifest_path)? {
+                if version <= current_version {
+                    tracing::debug!("Version changed to {} - stopping", version);
+                    return Ok((collected_commits, true));
                 }
             }
         }
 
-        collected_commits.push(commit_info);
+        collected_commits.extend(commit_info.expand_squashed(parsing));
     }
 
+    Ok((collected_commits, false))
+}
+
+/// The commits whose parents are cut off in a shallow clone (`.git/shallow`) or by `info/grafts`
+pub(super) fn grafted_commits(repo: &git2::Repository) -> HashSet<Oid> {
+    ["shallow", "info/grafts"]
+        .iter()
+        .filter_map(|name| std::fs::read_to_string(repo.path().join(name)).ok())
+        .flat_map(|data| {
+            data.lines()
+                .filter_map(|line| line.split_whitespace().next())
+                .filter_map(|oid| Oid::from_str(oid).ok())
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Counts the first-parent commits available from HEAD, up to and including the first graft
+fn history_depth(repo: &git2::Repository, grafts: &HashSet<Oid>) -> usize {
+    let Ok(mut revwalk) = repo.revwalk() else {
+        return 0;
+    };
+    if revwalk.push_head().and_then(|_| revwalk.simplify_first_parent()).is_err() {
+        return 0;
+    }
+    let mut depth = 0;
+    for oid in revwalk.flatten() {
+        depth += 1;
+        if grafts.contains(&oid) {
+            break;
+        }
+    }
+    depth
+}
+
+/// Expands the merge commits of a first-parent changelog with the commits they brought in
+///
+/// Each merge is followed by the commits reachable from its other parents but not from its
+/// first parent, so branches forked before the previous release still contribute their
+/// unreleased commits.  Commits are deduplicated and attributed to the merge through
+/// [`CommitInfo::merged_by`].
+pub(super) fn collect_merged_commits(
+    repo: &git2::Repository,
+    project_path: &Path,
+    mainline: Vec<CommitInfo>,
+    until: Option<Oid>,
+    paths: &PathFilters,
+    parsing: &ParseOptions,
+) -> Result<Vec<CommitInfo>, RepositoryError> {
+    let mut seen = mainline.iter().map(|c| c.id.clone()).collect::<HashSet<_>>();
+    let mut walked = HashSet::new();
+    let mut collected_commits = Vec::with_capacity(mainline.len());
+    for commit_info in mainline {
+        let oid = Oid::from_str(&commit_info.id).map_err(|_| RepositoryError::CommitNotFound(commit_info.id.clone()))?;
+        let merge = repo
+            .find_commit(oid)
+            .map_err(|_| RepositoryError::CommitNotFound(oid.to_string()))?;
+        collected_commits.push(commit_info);
+        // Expanded squash merges share an id; walk each merge once
+        if merge.parent_count() < 2 || !walked.insert(oid) {
+            continue;
+        }
+        for oid_result in merged_revwalk(repo, project_path, &merge, until, paths)? {
+            let (merged_oid, files_changed) = oid_result?;
+            if !seen.insert(merged_oid.to_string()) {
+                continue;
+            }
+            let (_, merged) = load_commit_info(repo, merged_oid, files_changed, parsing)?;
+            tracing::trace!("Commit {merged_oid} merged by {oid}");
+            collected_commits.extend(merged.with_merged_by(oid.to_string()).expand_squashed(parsing));
+        }
+    }
     Ok(collected_commits)
 }
 
+/// Loads a commit and parses its message, with any notes override applied, into a [`CommitInfo`]
+pub(super) fn load_commit_info<'repo>(repo: &'repo git2::Repository, oid: Oid, files_changed: Vec<PathBuf>, parsing: &ParseOptions) -> Result<(git2::Commit<'repo>, CommitInfo), RepositoryError> {
+    let commit = repo
+        .find_commit(oid)
+        .map_err(|_| RepositoryError::CommitNotFound(oid.to_string()))?;
+    let message = commit.message().unwrap_or_default();
+    let conventional_commit = match read_note_override(repo, oid) {
+        Some(note) => {
+            tracing::debug!("Applying {NOTES_REF} override to {oid}");
+            ConventionalCommit::parse_with(apply_note_override(message, &note), parsing)?
+        }
+        None => ConventionalCommit::parse_with(message, parsing)?,
+    };
+    let timestamp = commit.time().seconds();
+    let timestamp = timestamp.max(0) as u64;
+    let (name, email) = {
+        let author = commit.author();
+        (author.name().unwrap_or_default().to_string(), author.email().unwrap_or_default().to_string())
+    };
+    let commit_info = CommitInfo::new(oid.to_string(), files_changed, conventional_commit, timestamp)
+        .with_author(name, email)
+        .with_merge(commit.parent_count() > 1);
+    Ok((commit, commit_info))
+}
+
+/// Returns the manifest version set by `commit` when it differs from its first parent
+///
+/// Commits that touch the manifest without changing the version field (e.g. dependency
+/// updates) return `None`.  A commit whose parent has no readable manifest is treated as
+/// a version change.
+pub(super) fn version_change(repo: &git2::Repository, commit: &git2::Commit, relative_manifest_path: &Path) -> Result<Option<SimpleVersion>, RepositoryError> {
+    let version = manifest_version_at(repo, commit, relative_manifest_path)?;
+    let parent_version = commit.parents().next().and_then(|p| {
+        manifest_version_at(repo, &p, relative_manifest_path)
+            .map_err(|why| tracing::debug!("Could not read parent version: {why}"))
+            .ok()
+    });
+    match parent_version.as_ref() == Some(&version) {
+        true => Ok(None),
+        false => {
+            tracing::trace!("Version changed to {} (parent: {:?}) at {}", version, parent_version, commit.id());
+            Ok(Some(version))
+        }
+    }
+}
+
+/// Reads the manifest version as recorded in a specific commit
+pub(super) fn manifest_version_at(repo: &git2::Repository, commit: &git2::Commit, relative_manifest_path: &Path) -> Result<SimpleVersion, RepositoryError> {
+    let data = load_file_data(repo, commit, relative_manifest_path)?;
+    let version = SupportedManifest::parse(relative_manifest_path, &data)?.version()?;
+    Ok(version)
+}
+
 #[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
 pub struct CommitGroup {
     commit_type: CommitType,
@@ -76,6 +204,8 @@ impl CommitGroup {
 pub struct ChangeLog {
     pub current_version: SimpleVersion,
     pub changes: Vec<CommitInfo>,
+    /// Configured titles, ordering and visibility of commit types in the release notes
+    pub types: Vec<TypeDefinition>,
 }
 
 impl ChangeLog {
@@ -83,19 +213,107 @@ impl ChangeLog {
         Self {
             current_version: current_version.into(),
             changes: changes.as_ref().to_owned(),
+            types: vec![],
+        }
+    }
+
+    pub fn with_types(mut self, types: &[TypeDefinition]) -> Self {
+        self.types = types.to_vec();
+        self
+    }
+
+    fn type_definition(&self, commit_type: &CommitType) -> Option<&TypeDefinition> {
+        self.types.iter().find(|definition| definition.commit_type == *commit_type)
+    }
+
+    /// The release notes heading for a commit type
+    pub fn type_title(&self, commit_type: &CommitType) -> String {
+        match self
+            .type_definition(commit_type)
+            .and_then(|definition| definition.title.as_deref())
+        {
+            Some(title) => title.to_string(),
+            None => commit_type.as_release_note().to_string(),
         }
     }
 
+    fn type_order(&self, commit_type: &CommitType) -> i64 {
+        self.type_definition(commit_type)
+            .and_then(|definition| definition.order)
+            .unwrap_or_else(|| commit_type.order())
+    }
+
+    /// The version of the next release
+    ///
+    /// A `Release-As: x.y.z` trailer pins the version when it is ahead of the current version;
+    /// otherwise the highest bump across the changes is applied.
     pub fn next_version(&self, rules: &[(CommitType, BumpRule)]) -> SimpleVersion {
         let rules = rules.to_vec();
         let version = self.current_version;
-        let max_bump = self
-            .changes
+        let changes = self.effective_changes();
+        let release_as = changes
             .iter()
+            .filter_map(|commit| commit.commit.release_as())
+            .filter(|release_as| {
+                let ahead = *release_as > version;
+                if !ahead {
+                    tracing::warn!("Ignoring Release-As: {release_as} - not ahead of current version {version}");
+                }
+                ahead
+            })
+            .max();
+        if let Some(release_as) = release_as {
+            return release_as;
+        }
+        let max_bump = changes
+            .into_iter()
             .fold(BumpRule::default(), |max_bump, commit| max_bump.max(commit.rule(&rules)));
         version.bump(max_bump)
     }
 
+    /// The changes that count towards the release
+    ///
+    /// A revert and the commit it reverts cancel out when both fall within this changelog, so
+    /// neither affects the bump or the release notes.  Reverts of commits released earlier
+    /// are kept.  Commits with a `Semrel-Skip: true` trailer are left out.
+    pub fn effective_changes(&self) -> Vec<&CommitInfo> {
+        let mut dropped = self.changes.iter().map(|change| change.commit.is_skipped()).collect::<Vec<_>>();
+        for (index, revert) in self.changes.iter().enumerate() {
+            if dropped[index] {
+                continue;
+            }
+            let reverted_id = revert.commit.reverted_id();
+            let reverted_header = revert.commit.reverted_header();
+            if reverted_id.is_none() && reverted_header.is_none() {
+                continue;
+            }
+            // Changes are ordered newest first, so the original follows its revert
+            let candidates = || self.changes.iter().enumerate().skip(index + 1).filter(|(i, _)| !dropped[*i]);
+            // An id that matches nothing here (e.g. a rebased commit) falls back to the header
+            let original = reverted_id
+                .and_then(|id| candidates().find(|(_, original)| original.id.starts_with(id)))
+                .or_else(|| {
+                    let header = reverted_header?;
+                    candidates().find(|(_, original)| original.message().lines().next() == Some(header) || original.commit.subject == header)
+                });
+            if let Some((_, original)) = original {
+                tracing::debug!("Commit {} reverts {} - dropping both", revert.id, original.id);
+                dropped[index] = true;
+                for (i, change) in self.changes.iter().enumerate() {
+                    // Expanded squash merges share the id of the reverted commit
+                    if change.id == original.id {
+                        dropped[i] = true;
+                    }
+                }
+            }
+        }
+        self.changes
+            .iter()
+            .zip(dropped)
+            .filter_map(|(change, dropped)| (!dropped).then_some(change))
+            .collect()
+    }
+
     /// Generates a release notes for the changelog
     ///
     /// The release notes are generated by organizing the commits into sections based on the commit type
@@ -114,11 +332,26 @@ impl ChangeLog {
     /// - Others
     /// ```
     pub fn release_notes(&self, rules: &[(CommitType, BumpRule)]) -> String {
+        let today = chrono::Local::now().date_naive();
+        self.release_notes_for(self.next_version(rules), today)
+    }
+
+    /// Generates release notes for an explicit version and release date
+    ///
+    /// Used when rendering notes for a release that already happened, where the version
+    /// and date come from the release commit rather than from the rules and the clock.
+    pub fn release_notes_for(&self, version: SimpleVersion, date: chrono::NaiveDate) -> String {
         let aggregated_commits = self.aggregated_commits();
-        let today = chrono::Local::now();
-        let mut notes = format!("# Release notes: {} ({})\n", self.next_version(rules), today.format("%Y-%m-%d"));
+        let mut notes = format!("# Release notes: {} ({})\n", version, date.format("%Y-%m-%d"));
+        let breaking_changes = self.breaking_changes();
+        if !breaking_changes.is_empty() {
+            notes.push_str("\n\n## Breaking Changes\n");
+            for breaking_change in breaking_changes {
+                notes.push_str(&format!("- {}\n", breaking_change.replace('\n', "\n  ")));
+            }
+        }
         for commit_group in aggregated_commits {
-            notes.push_str(&format!("\n\n## {}\n", commit_group.commit_type.as_release_note()));
+            notes.push_str(&format!("\n\n## {}\n", self.type_title(&commit_group.commit_type)));
             for (scope, commits) in commit_group.scopes {
                 if !scope.is_empty() {
                     notes.push_str(&format!("\n### {scope}\n"));
@@ -127,16 +360,76 @@ impl ChangeLog {
                     if commit.commit_type().as_str().starts_with("semrel") {
                         continue;
                     }
-                    notes.push_str(&format!("- {}\n", commit.commit.subject));
+                    match commit.merged_by.as_deref() {
+                        Some(merge_id) => notes.push_str(&format!("- {} ({})\n", commit.commit.subject, self.merge_label(merge_id))),
+                        None => notes.push_str(&format!("- {}\n", commit.commit.subject)),
+                    }
                 }
             }
         }
         notes
     }
 
+    /// The descriptions of the breaking changes in this release, newest first
+    ///
+    /// A commit marked with `!` but without a `BREAKING CHANGE:` description is described by its subject.
+    pub fn breaking_changes(&self) -> Vec<String> {
+        let merges = self.merge_ids();
+        let mut descriptions = vec![];
+        for commit_info in self.effective_changes() {
+            let commit = &commit_info.commit;
+            if !commit.is_breaking() || commit.commit_type.as_str().starts_with("semrel") {
+                continue;
+            }
+            if is_represented_merge(&merges, commit_info) {
+                continue;
+            }
+            let scope = commit
+                .scope
+                .as_deref()
+                .map(|scope| format!("**{scope}**: "))
+                .unwrap_or_default();
+            match commit.breaking_changes.is_empty() {
+                true => descriptions.push(format!("{scope}{}", commit.subject)),
+                false => descriptions.extend(
+                    commit
+                        .breaking_changes
+                        .iter()
+                        .map(|description| format!("{scope}{description}")),
+                ),
+            }
+        }
+        descriptions
+    }
+
+    /// The ids of the merges that brought in other changes
+    fn merge_ids(&self) -> HashSet<&str> {
+        self.changes.iter().filter_map(|c| c.merged_by.as_deref()).collect()
+    }
+
+    /// A short reference to a merge: the pull request number from its message, or its short id
+    fn merge_label(&self, merge_id: &str) -> String {
+        let pull_request = self.changes.iter().find(|c| c.id == merge_id).and_then(|merge| {
+            merge.message().split_whitespace().find_map(|word| {
+                let number = word
+                    .trim_matches(|c: char| !c.is_ascii_alphanumeric() && c != '#')
+                    .strip_prefix('#')?;
+                (!number.is_empty() && number.chars().all(|c| c.is_ascii_digit())).then(|| format!("#{number}"))
+            })
+        });
+        pull_request.unwrap_or_else(|| merge_id.chars().take(7).collect())
+    }
+
+    /// The changes grouped by commit type and scope, in release notes order
+    ///
+    /// Hidden types are left out.
     pub fn aggregated_commits(&self) -> Vec<CommitGroup> {
+        let merges = self.merge_ids();
         let mut map: HashMap<CommitType, HashMap<String, Vec<CommitInfo>>> = HashMap::new();
-        for commit_info in &self.changes {
+        for commit_info in self.effective_changes() {
+            if is_represented_merge(&merges, commit_info) {
+                continue;
+            }
             let commit_type = commit_info.commit.commit_type.clone();
             let scope = commit_info.commit.scope.clone().unwrap_or_default();
             let entry = map.entry(commit_type).or_default();
@@ -147,15 +440,113 @@ impl ChangeLog {
         let mut vec: Vec<CommitGroup> = map
             .into_iter()
             .filter(|(commit_type, _)| !ignored.iter().any(|s| commit_type.as_str().starts_with(s)))
+            .filter(|(commit_type, _)| !self.type_definition(commit_type).is_some_and(|definition| definition.hidden))
             .map(|(commit_type, scopes)| CommitGroup::new(commit_type, scopes.into_iter().collect()))
             .collect();
-        vec.sort();
+        vec.sort_by(|a, b| (self.type_order(&a.commit_type), a).cmp(&(self.type_order(&b.commit_type), b)));
         vec
     }
 }
 
+/// Merges are represented by the commits they brought in, and merges inside a merged branch
+/// (e.g. `Merge branch 'main' into login`) by the merge that brought in the branch
+fn is_represented_merge(merges: &HashSet<&str>, commit_info: &CommitInfo) -> bool {
+    merges.contains(commit_info.id.as_str()) || (commit_info.is_merge && commit_info.merged_by.is_some())
+}
+
+/// How the history behind HEAD is traversed when collecting commits
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum HistoryMode {
+    /// Follow only the first parent of each commit
+    #[default]
+    FirstParent,
+    /// Also include the commits brought in by merge commits
+    AllParents,
+}
+
+/// Options controlling how a changelog is collected
+#[derive(Debug, Default, Clone)]
+pub struct ChangeLogOptions {
+    /// How merge commits are traversed
+    pub history: HistoryMode,
+    /// Collect commits after this commit instead of after the previous release
+    ///
+    /// The current version is read from the manifest at this commit.
+    pub from: Option<Oid>,
+    /// Collect commits up to this commit instead of HEAD
+    ///
+    /// Without `from`, the current version is read from the manifest at this commit.
+    pub to: Option<Oid>,
+    /// Include and exclude patterns that decide which commits belong to the project
+    pub paths: PathFilters,
+    /// Configured aliases and patterns, applied when commit messages are parsed
+    pub parsing: ParseOptions,
+    /// Configured commit types, carried into the changelog for its release notes
+    pub types: Vec<TypeDefinition>,
+    /// Keep the commits a shallow clone holds instead of failing when its history ends before
+    /// the previous release, for callers that only need the manifest version
+    pub allow_shallow: bool,
+}
+
 /// Collects all commits since the last release and computes the next version.
 pub fn get_changelog(repo: &git2::Repository, manifest_path: impl Into<PathBuf>) -> Result<ChangeLog, RepositoryError> {
+    get_changelog_with(repo, manifest_path, &ChangeLogOptions::default())
+}
+
+/// Collects all commits since the last release using explicit [`ChangeLogOptions`]
+pub fn get_changelog_with(repo: &git2::Repository, manifest_path: impl Into<PathBuf>, options: &ChangeLogOptions) -> Result<ChangeLog, RepositoryError> {
+    let (manifest_path, relative_manifest_path) = resolve_manifest_path(manifest_path)?;
+    let current_version = match options.from.or(options.to) {
+        Some(oid) => {
+            let commit = repo
+                .find_commit(oid)
+                .map_err(|_| RepositoryError::CommitNotFound(oid.to_string()))?;
+            manifest_version_at(repo, &commit, &relative_manifest_path)?
+        }
+        None => {
+            let manifest = SupportedManifest::try_from(manifest_path.to_owned()).map_err(|err| {
+                tracing::error!("Failed to get manifest: {err}");
+                err
+            })?;
+            manifest.version()?
+        }
+    };
+    tracing::debug!("Current version: {}", current_version);
+
+    let walker = revwalk_from(repo, &manifest_path, options.to, options.from, &options.paths)?;
+    let (captured_commits, boundary_found) = collect_commits(repo, walker, &relative_manifest_path, current_version, &options.parsing)?;
+    // An explicit range does not need to reach the previous release
+    if options.from.is_none() && !options.allow_shallow {
+        check_shallow_boundary(repo, boundary_found)?;
+    }
+    let captured_commits = match options.history {
+        HistoryMode::FirstParent => captured_commits,
+        HistoryMode::AllParents => collect_merged_commits(repo, &manifest_path, captured_commits, options.from, &options.paths, &options.parsing)?,
+    };
+
+    let changelog = ChangeLog::new(current_version, captured_commits).with_types(&options.types);
+    tracing::debug!("Finished get_changelog. Current version: {}", current_version);
+    Ok(changelog)
+}
+
+/// Fails when the history of a shallow clone ends before the previous release was found
+pub(super) fn check_shallow_boundary(repo: &git2::Repository, boundary_found: bool) -> Result<(), RepositoryError> {
+    let grafts = grafted_commits(repo);
+    if !repo.is_shallow() && grafts.is_empty() {
+        return Ok(());
+    }
+    match boundary_found {
+        true => {
+            tracing::warn!("Repository is a shallow clone; the previous release was found within the fetched history");
+            Ok(())
+        }
+        false => Err(RepositoryError::ShallowRepository(history_depth(repo, &grafts))),
+    }
+}
+
+/// Resolves a manifest path into its canonical form and its path relative to the repository root
+pub(super) fn resolve_manifest_path(manifest_path: impl Into<PathBuf>) -> Result<(PathBuf, PathBuf), RepositoryError> {
     let manifest_path: PathBuf = manifest_path.into();
     let manifest_path = manifest_path.canonicalize().unwrap_or(manifest_path);
     tracing::trace!("Getting changelog for manifest path: {}", manifest_path.display());
@@ -185,19 +576,7 @@ pub fn get_changelog(repo: &git2::Repository, manifest_path: impl Into<PathBuf>)
     tracing::trace!("Searching for relative manifest path: {}", relative_manifest_path.display());
     let relative_project_path = compute_relative_path(&repo_path, project_path);
     tracing::debug!("Starting get_changelog for path: {}", relative_project_path.display());
-    let manifest = SupportedManifest::try_from(manifest_path.to_owned()).map_err(|err| {
-        tracing::error!("Failed to get manifest: {err}");
-        err
-    })?;
-    let current_version = manifest.version()?;
-    tracing::debug!("Current version: {}", current_version);
-
-    // Use the optimized streaming approach that stops early
-    let captured_commits = collect_changelog_commits_streaming(repo, &manifest_path, &relative_manifest_path, current_version)?;
-
-    let changelog = ChangeLog::new(current_version, captured_commits);
-    tracing::debug!("Finished get_changelog. Current version: {}", current_version);
-    Ok(changelog)
+    Ok((manifest_path, relative_manifest_path))
 }
 
 /// Retrieves the data of a file in a specific commit
@@ -211,7 +590,7 @@ pub fn get_changelog(repo: &git2::Repository, manifest_path: impl Into<PathBuf>)
 /// # Returns
 ///
 /// * `Result<String, RepositoryError>` - The file data as a string if successful, or an error
-fn load_file_data(repo: &git2::Repository, commit: &git2::Commit, path: impl AsRef<Path>) -> Result<String, RepositoryError> {
+pub(super) fn load_file_data(repo: &git2::Repository, commit: &git2::Commit, path: impl AsRef<Path>) -> Result<String, RepositoryError> {
     let path = path.as_ref();
     let oid = commit.id();
     tracing::trace!("Loading file data for path: {} using commit id: {}", path.display(), oid);
@@ -274,6 +653,87 @@ fn get_files_changed(repo: &git2::Repository, oid: impl Into<git2::Oid>) -> Resu
 
 #[allow(clippy::needless_lifetimes)]
 pub fn revwalk<'a>(repo: &'a git2::Repository, project_path: impl Into<PathBuf>) -> Result<impl IntoIterator<Item = Result<(Oid, Vec<PathBuf>), RepositoryError>> + 'a, RepositoryError> {
+    static NO_FILTERS: PathFilters = PathFilters::none();
+    revwalk_from(repo, project_path, None, None, &NO_FILTERS)
+}
+
+/// Walks the first-parent history starting at `start`, or at HEAD when no start is given
+///
+/// When `until` is given, the walk excludes that commit and everything reachable from it.
+pub(super) fn revwalk_from<'a>(
+    repo: &'a git2::Repository,
+    project_path: impl Into<PathBuf>,
+    start: Option<Oid>,
+    until: Option<Oid>,
+    paths: &'a PathFilters,
+) -> Result<impl Iterator<Item = Result<(Oid, Vec<PathBuf>), RepositoryError>> + 'a, RepositoryError> {
+    let mut revwalk = repo.revwalk().map_err(|why| {
+        tracing::error!("Failed to create revwalk: {why}");
+        RepositoryError::InvalidRepository(why.to_string())
+    })?;
+    match start {
+        Some(oid) => revwalk.push(oid).map_err(|why| {
+            tracing::error!("Failed to push {oid}: {why}");
+            RepositoryError::InvalidRepository(why.to_string())
+        })?,
+        None => revwalk.push_head().map_err(|why| {
+            tracing::error!("Failed to push head: {why}");
+            RepositoryError::InvalidRepository(why.to_string())
+        })?,
+    }
+    if let Some(oid) = until {
+        revwalk.hide(oid).map_err(|why| {
+            tracing::error!("Failed to hide {oid}: {why}");
+            RepositoryError::InvalidRepository(why.to_string())
+        })?;
+    }
+    revwalk.set_sorting(git2::Sort::TOPOLOGICAL).map_err(|why| {
+        tracing::error!("Failed to sort repo: {why}");
+        RepositoryError::InvalidRepository(why.to_string())
+    })?;
+    revwalk.simplify_first_parent().map_err(|why| {
+        tracing::error!("Failed to simplify: {why}");
+        RepositoryError::InvalidRepository(why.to_string())
+    })?;
+    filter_project_commits(repo, project_path, revwalk, paths)
+}
+
+/// Walks the commits a merge brought in: reachable from its other parents but not its first parent
+fn merged_revwalk<'a>(
+    repo: &'a git2::Repository,
+    project_path: impl Into<PathBuf>,
+    merge: &git2::Commit,
+    until: Option<Oid>,
+    paths: &'a PathFilters,
+) -> Result<impl Iterator<Item = Result<(Oid, Vec<PathBuf>), RepositoryError>> + 'a, RepositoryError> {
+    let mut revwalk = repo
+        .revwalk()
+        .map_err(|why| RepositoryError::InvalidRepository(why.to_string()))?;
+    for (index, parent_id) in merge.parent_ids().enumerate() {
+        let result = match index {
+            0 => revwalk.hide(parent_id),
+            _ => revwalk.push(parent_id),
+        };
+        result.map_err(|why| RepositoryError::InvalidRepository(why.to_string()))?;
+    }
+    if let Some(oid) = until {
+        revwalk
+            .hide(oid)
+            .map_err(|why| RepositoryError::InvalidRepository(why.to_string()))?;
+    }
+    revwalk
+        .set_sorting(git2::Sort::TOPOLOGICAL)
+        .map_err(|why| RepositoryError::InvalidRepository(why.to_string()))?;
+    filter_project_commits(repo, project_path, revwalk, paths)
+}
+
+/// Pairs each walked commit with its changed files, keeping only commits that touch the project
+fn filter_project_commits<'a>(
+    repo: &'a git2::Repository,
+    project_path: impl Into<PathBuf>,
+    revwalk: git2::Revwalk<'a>,
+    paths: &'a PathFilters,
+) -> Result<impl Iterator<Item = Result<(Oid, Vec<PathBuf>), RepositoryError>> + 'a, RepositoryError> {
     let project_path = project_path.into();
     let repo_path = find_top_of_repo(&project_path)?;
     let canonical = project_path
@@ -288,23 +748,7 @@ pub fn revwalk<'a>(repo: &'a git2::Repository, project_path: impl Into<PathBuf>)
             .canonicalize()
             .map_err(|_| RepositoryError::InvalidRepositoryPath(project_path))?
     };
-
-    let mut revwalk = repo.revwalk().map_err(|why| {
-        tracing::error!("Failed to create revwalk: {why}");
-        RepositoryError::InvalidRepository(why.to_string())
-    })?;
-    revwalk.push_head().map_err(|why| {
-        tracing::error!("Failed to push head: {why}");
-        RepositoryError::InvalidRepository(why.to_string())
-    })?;
-    revwalk.set_sorting(git2::Sort::TOPOLOGICAL).map_err(|why| {
-        tracing::error!("Failed to sort repo: {why}");
-        RepositoryError::InvalidRepository(why.to_string())
-    })?;
-    revwalk.simplify_first_parent().map_err(|why| {
-        tracing::error!("Failed to simplify: {why}");
-        RepositoryError::InvalidRepository(why.to_string())
-    })?;
+    let matcher = ProjectMatcher::new(compute_relative_path(&repo_path, &project_path), paths)?;
 
     let data = revwalk
         .map(|oid| oid.map_err(|why| RepositoryError::InvalidRepository(why.to_string())))
@@ -314,7 +758,7 @@ pub fn revwalk<'a>(repo: &'a git2::Repository, project_path: impl Into<PathBuf>)
             if files.is_empty() {
                 return Ok(None);
             }
-            let matched = files.iter().any(|file| repo_path.join(file).starts_with(&project_path));
+            let matched = files.iter().any(|file| matcher.matches(file));
             Ok(matched.then_some((oid, files)))
         })
         .filter_map(|result| result.transpose());
@@ -369,9 +813,11 @@ fn compute_relative_path(repo_path: impl AsRef<Path>, project_path: impl AsRef<P
 mod tests {
     use super::*;
 
-    use git2::{Oid, Repository, Signature};
+    use crate::core::git::commit_info::change;
+    use crate::core::git::test_repo::TestRepo;
+    use crate::write_note_override;
+    use git2::Oid;
     use rstest::rstest;
-    use tempfile::TempDir;
 
     #[derive(Debug, Clone)]
     struct CommitWithVersion {
@@ -619,134 +1065,6 @@ mod tests {
         }
     }
 
-    struct TestRepo {
-        temp_dir: TempDir,
-        repo: Repository,
-    }
-
-    impl TestRepo {
-        fn new() -> Self {
-            // Default behavior is to setup tracing to error if the RUST_LOG variable is not set
-            //  This code will only setup tracing if the RUST_LOG variable is set
-            if std::env::var("RUST_LOG").is_ok() {
-                tracing_subscriber::fmt()
-                    .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
-                    .try_init()
-                    .ok();
-            }
-            let temp_dir = TempDir::new().unwrap();
-            let temp_path = temp_dir.path().join("repo");
-            let repo = Repository::init(&temp_path).unwrap();
-            println!("Created repository here: {}", &temp_path.canonicalize().unwrap().display());
-            TestRepo { temp_dir, repo }
-        }
-
-        fn path(&self) -> PathBuf {
-            self.temp_dir.path().join("repo")
-        }
-
-        fn commit(&self, message: &str) -> Result<Oid, RepositoryError> {
-            let sig = Signature::now("Test", "test@example.com").unwrap();
-            let tree_id = {
-                let mut index = self
-                    .repo
-                    .index()
-                    .map_err(|_| RepositoryError::InvalidRepositoryPath(self.path().to_path_buf()))?;
-                index
-                    .write_tree()
-                    .map_err(|_| RepositoryError::InvalidRepositoryPath(self.path().to_path_buf()))?
-            };
-            let tree = self
-                .repo
-                .find_tree(tree_id)
-                .map_err(|_| RepositoryError::InvalidRepositoryPath(self.path().to_path_buf()))?;
-            let parent_commit = self
-                .repo
-                .head()
-                .ok()
-                .and_then(|h| h.target())
-                .and_then(|t| self.repo.find_commit(t).ok());
-            let parents = parent_commit.as_ref().map(|p| vec![p]).unwrap_or_default();
-            self.repo
-                .commit(Some("HEAD"), &sig, &sig, message, &tree, &parents)
-                .map_err(|_| RepositoryError::InvalidRepositoryPath(self.path().to_path_buf()))
-        }
-
-        fn add_file(&self, path: impl AsRef<Path>, content: impl AsRef<str>) -> Result<(), RepositoryError> {
-            use std::fs::File;
-            use std::io::Write;
-            let file_path = self.path().join(path.as_ref());
-            let mut file = File::create(&file_path).unwrap();
-            file.write_all(content.as_ref().as_bytes()).unwrap();
-
-            let mut index = self.repo.index().unwrap();
-            index.add_path(path.as_ref()).unwrap();
-            index.write_tree().unwrap();
-
-            Ok(())
-        }
-
-        #[allow(dead_code)]
-        fn branch(&self, name: impl AsRef<str>) -> Result<(), RepositoryError> {
-            let branch_name = name.as_ref();
-            println!("Switching to branch '{}'", branch_name); // Debug message
-            match self.repo.find_branch(branch_name, git2::BranchType::Local) {
-                Ok(_) => {
-                    // Branch exists, so check it out
-                    let obj = self.repo.revparse_single(&format!("refs/heads/{}", branch_name)).unwrap();
-                    let mut checkout_builder = git2::build::CheckoutBuilder::new();
-                    checkout_builder.force();
-                    match self.repo.checkout_tree(&obj, None) {
-                        Ok(_) => {
-                            self.repo.set_head(&format!("refs/heads/{}", branch_name)).unwrap();
-                            println!("Switched to existing branch '{}'", branch_name);
-                        }
-                        Err(why) => {
-                            println!("Failed to checkout tree: {why}");
-                            checkout_builder.remove_untracked(true);
-                            match self.repo.checkout_tree(&obj, Some(&mut checkout_builder)) {
-                                Ok(_) => {
-                                    self.repo.set_head(&format!("refs/heads/{}", branch_name)).unwrap();
-                                    println!("Switched to existing branch '{}'", branch_name);
-                                }
-                                Err(why) => {
-                                    println!("Failed to checkout tree: {why}");
-                                    self.repo.set_head(&format!("refs/heads/{}", branch_name)).unwrap();
-                                    println!("Switched to existing branch '{}'", branch_name);
-                                }
-                            }
-                        }
-                    }
-                    self.repo.set_head(&format!("refs/heads/{}", branch_name)).unwrap();
-                    println!("Switched to existing branch '{}'", branch_name);
-                }
-                Err(_) => {
-                    // Branch does not exist, so create it
-                    let head_ref = self.repo.head().unwrap();
-                    let head_commit = head_ref.peel_to_commit().unwrap();
-                    let _branch = self.repo.branch(branch_name, &head_commit, false).unwrap();
-
-                    // Checkout the newly created branch
-                    let obj = self.repo.revparse_single(&format!("refs/heads/{}", branch_name)).unwrap();
-                    let mut checkout_builder = git2::build::CheckoutBuilder::new();
-                    checkout_builder.force(); // Force checkout to overcome conflicts
-                    match self.repo.checkout_tree(&obj, Some(&mut checkout_builder)) {
-                        Ok(_) => println!("Created and switched to new branch '{}'", branch_name),
-                        Err(e) => {
-                            println!("Failed to checkout tree: {e}");
-                            checkout_builder.remove_untracked(true);
-                            self.repo.set_head(&format!("refs/heads/{}", branch_name)).unwrap();
-                            println!("Switched to existing branch '{}'", branch_name);
-                        }
-                    }
-                    self.repo.set_head(&format!("refs/heads/{}", branch_name)).unwrap();
-                }
-            }
-
-            Ok(())
-        }
-    }
-
     #[test]
     fn case_empty() {
         let test_repo = TestRepo::new();
@@ -1952,4 +2270,391 @@ mod tests {
         assert!(!messages.iter().any(|m| m == "fix: fix B"), "fix B should NOT be in changelog (before 1.1.2): {messages:?}");
         assert!(!messages.iter().any(|m| m == "fix: fix A"), "fix A should NOT be in changelog (before 1.1.1): {messages:?}");
     }
+
+    /// 0.1.0 -> docs on main, with a feature branch merged back in through a merge commit
+    fn build_merged_branch() -> (TestRepo, Oid) {
+        let test_repo = TestRepo::new();
+        test_repo
+            .add_file("Cargo.toml", "[package]\nname = \"test\"\nversion = \"0.1.0\"\n")
+            .unwrap();
+        let base = test_repo.commit("semrel: 0.1.0").unwrap();
+        test_repo.add_file("login.rs", "fn login() {}").unwrap();
+        test_repo.commit("feat: add login").unwrap();
+        test_repo.add_file("login.rs", "fn login() { todo!() }").unwrap();
+        let branch = test_repo.commit("fix: handle empty password").unwrap();
+        test_repo.reset(base);
+        test_repo.add_file("README.md", "docs").unwrap();
+        test_repo.commit("docs: readme").unwrap();
+        test_repo.add_file("login.rs", "fn login() { todo!() }").unwrap();
+        let merge = test_repo.merge("Merge pull request #12 from user/login", branch);
+        (test_repo, merge)
+    }
+
+    #[test]
+    fn first_parent_history_hides_merged_commits() {
+        let (test_repo, _) = build_merged_branch();
+        let manifest_path = test_repo.path().join("Cargo.toml");
+        let changelog = get_changelog(&test_repo.repo, &manifest_path).unwrap();
+        let messages: Vec<String> = changelog.changes.iter().map(|c| c.commit.message()).collect();
+
+        assert_eq!(messages, vec!["Merge pull request #12 from user/login", "docs: readme"]);
+    }
+
+    #[test]
+    fn all_parents_history_attributes_merged_commits() {
+        let (test_repo, merge) = build_merged_branch();
+        let manifest_path = test_repo.path().join("Cargo.toml");
+        let options = ChangeLogOptions {
+            history: HistoryMode::AllParents,
+            ..Default::default()
+        };
+        let changelog = get_changelog_with(&test_repo.repo, &manifest_path, &options).unwrap();
+        let messages: Vec<String> = changelog.changes.iter().map(|c| c.commit.message()).collect();
+        let merged_by: Vec<Option<String>> = changelog.changes.iter().map(|c| c.merged_by.clone()).collect();
+
+        assert_eq!(
+            messages,
+            vec!["Merge pull request #12 from user/login", "fix: handle empty password", "feat: add login", "docs: readme"]
+        );
+        assert_eq!(merged_by, vec![None, Some(merge.to_string()), Some(merge.to_string()), None]);
+        assert_eq!(changelog.next_version(&[]), SimpleVersion::new(0, 2, 0));
+
+        let notes = changelog.release_notes_for(SimpleVersion::new(0, 2, 0), chrono::NaiveDate::default());
+        assert!(notes.contains("- add login (#12)"), "{notes}");
+        assert!(notes.contains("- handle empty password (#12)"), "{notes}");
+        assert!(!notes.contains("Merge pull request"), "{notes}");
+    }
+
+    #[test]
+    fn nested_merges_are_left_out_of_release_notes() {
+        let changelog = ChangeLog::new(
+            SimpleVersion::new(0, 1, 0),
+            [
+                change("m1", "Merge pull request #12 from user/login").with_merge(true),
+                change("m2", "Merge branch 'main' into login")
+                    .with_merge(true)
+                    .with_merged_by("m1"),
+                change("c1", "feat!: add login").with_merged_by("m1"),
+            ],
+        );
+
+        let notes = changelog.release_notes_for(SimpleVersion::new(1, 0, 0), chrono::NaiveDate::default());
+
+        assert!(notes.contains("- add login (#12)"), "{notes}");
+        assert!(!notes.contains("Merge"), "{notes}");
+        assert_eq!(changelog.breaking_changes(), vec!["add login"]);
+    }
+
+    #[test]
+    fn aliases_apply_to_parsing_and_grouping() {
+        let test_repo = TestRepo::new();
+        test_repo
+            .add_file("Cargo.toml", "[package]\nname = \"test\"\nversion = \"0.1.0\"\n")
+            .unwrap();
+        test_repo.commit("semrel: 0.1.0").unwrap();
+        test_repo.add_file("a.rs", "fn a() {}").unwrap();
+        test_repo.commit("bugfix: handle timeout").unwrap();
+        test_repo.add_file("b.rs", "fn b() {}").unwrap();
+        test_repo.commit("deploy: ship to staging").unwrap();
+
+        let manifest_path = test_repo.path().join("Cargo.toml");
+        let options = ChangeLogOptions {
+            parsing: ParseOptions {
+                aliases: crate::CommitTypeAliases::new([("bugfix".to_string(), CommitType::Fix)], ["deploy".to_string()]),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let changelog = get_changelog_with(&test_repo.repo, &manifest_path, &options).unwrap();
+        let types: Vec<&CommitType> = changelog.changes.iter().map(|c| c.commit_type()).collect();
+
+        assert_eq!(types, vec![&CommitType::Custom("deploy".to_string()), &CommitType::Fix]);
+        assert_eq!(changelog.next_version(&[]), SimpleVersion::new(0, 1, 1));
+        let notes = changelog.release_notes_for(SimpleVersion::new(0, 1, 1), chrono::NaiveDate::default());
+        assert!(notes.contains("## Fixes\n- handle timeout"), "{notes}");
+        assert!(notes.contains("## deploy\n- ship to staging"), "{notes}");
+    }
+
+    #[test]
+    fn squash_merge_body_is_expanded() {
+        let test_repo = TestRepo::new();
+        test_repo
+            .add_file("Cargo.toml", "[package]\nname = \"test\"\nversion = \"0.1.0\"\n")
+            .unwrap();
+        test_repo.commit("semrel: 0.1.0").unwrap();
+        test_repo.add_file("login.rs", "fn login() {}").unwrap();
+        let squash = test_repo
+            .commit("Feature/login (#42)\n\n* feat(auth): add login\n\n* fix: handle timeout")
+            .unwrap();
+
+        let manifest_path = test_repo.path().join("Cargo.toml");
+        let changelog = get_changelog(&test_repo.repo, &manifest_path).unwrap();
+        let messages: Vec<String> = changelog.changes.iter().map(|c| c.commit.message()).collect();
+
+        assert_eq!(messages, vec!["feat(auth): add login", "fix: handle timeout"]);
+        assert!(changelog.changes.iter().all(|c| c.id == squash.to_string()));
+        assert_eq!(changelog.next_version(&[]), SimpleVersion::new(0, 2, 0));
+        let notes = changelog.release_notes_for(SimpleVersion::new(0, 2, 0), chrono::NaiveDate::default());
+        assert!(notes.contains("### auth\n- add login"), "{notes}");
+        assert!(notes.contains("- handle timeout"), "{notes}");
+    }
+
+    #[test]
+    fn notes_override_commit_messages() {
+        let test_repo = TestRepo::new();
+        test_repo
+            .add_file("Cargo.toml", "[package]\nname = \"test\"\nversion = \"0.1.0\"\n")
+            .unwrap();
+        test_repo.commit("semrel: 0.1.0").unwrap();
+        test_repo.add_file("login.rs", "fn login() {}").unwrap();
+        let mistyped = test_repo.commit("feat: fix login timeout").unwrap();
+        test_repo.add_file("docs.md", "# Docs").unwrap();
+        let docs = test_repo.commit("docs: rewrite guide").unwrap();
+        let mut config = test_repo.repo.config().unwrap();
+        config.set_str("user.name", "Test").unwrap();
+        config.set_str("user.email", "test@example.com").unwrap();
+        write_note_override(&test_repo.repo, mistyped, "fix: fix login timeout").unwrap();
+        write_note_override(&test_repo.repo, docs, "Semrel-Bump: minor").unwrap();
+
+        let manifest_path = test_repo.path().join("Cargo.toml");
+        let changelog = get_changelog(&test_repo.repo, &manifest_path).unwrap();
+        let types: Vec<&CommitType> = changelog.changes.iter().map(|c| c.commit_type()).collect();
+
+        assert_eq!(types, vec![&CommitType::Docs, &CommitType::Fix]);
+        assert_eq!(changelog.next_version(&[]), SimpleVersion::new(0, 2, 0));
+    }
+
+    #[rstest]
+    #[case::git_revert(
+        vec![change("bbbbbbb", "Revert \"feat: add login\"\n\nThis reverts commit aaa1111."), change("aaa1111", "feat: add login"), change("ccccccc", "fix: typo")],
+        vec!["fix: typo"],
+        "0.1.1"
+    )]
+    #[case::conventional_revert(vec![change("bbb", "revert: feat: add login"), change("aaa", "feat: add login"), change("ccc", "fix: typo")], vec!["fix: typo"], "0.1.1")]
+    #[case::revert_by_subject(vec![change("bbb", "revert: add login"), change("aaa", "feat: add login")], vec![], "0.1.0")]
+    #[case::original_released_earlier(vec![change("bbb", "revert: feat: add login"), change("ccc", "fix: typo")], vec!["revert: feat: add login", "fix: typo"], "0.1.1")]
+    #[case::revert_of_revert(
+        vec![
+            change("ccccccc", "Revert \"Revert \"feat: add login\"\"\n\nThis reverts commit bbbbbbb."),
+            change("bbbbbbb", "Revert \"feat: add login\"\n\nThis reverts commit aaaaaaa."),
+            change("aaaaaaa", "feat: add login"),
+        ],
+        vec!["feat: add login"],
+        "0.2.0"
+    )]
+    #[case::hex_word_is_not_an_id(
+        vec![change("bbbbbbb", "revert: fix typo\n\nThis reverts commit add."), change("add1234", "feat: add login")],
+        vec!["revert: fix typo\n\nThis reverts commit add.", "feat: add login"],
+        "0.2.0"
+    )]
+    #[case::unknown_id_falls_back_to_header(
+        vec![change("bbbbbbb", "Revert \"feat: add login\"\n\nThis reverts commit 1234567."), change("aaaaaaa", "feat: add login"), change("ccccccc", "fix: typo")],
+        vec!["fix: typo"],
+        "0.1.1"
+    )]
+    fn test_reverts_cancel_out(#[case] changes: Vec<CommitInfo>, #[case] expected: Vec<&str>, #[case] expected_version: &str) {
+        let changelog = ChangeLog::new(SimpleVersion::new(0, 1, 0), changes);
+        let effective = changelog.effective_changes().iter().map(|c| c.message()).collect::<Vec<_>>();
+        assert_eq!(effective, expected);
+        assert_eq!(changelog.next_version(&[]).to_string(), expected_version);
+    }
+
+    #[rstest]
+    #[case::bump_none(vec![change("aaa", "feat: add login\n\nSemrel-Bump: none"), change("bbb", "fix: typo")], "0.1.1")]
+    #[case::bump_major(vec![change("aaa", "docs: rewrite guide\n\nSemrel-Bump: major"), change("bbb", "feat: add login")], "1.0.0")]
+    #[case::bump_overrides_breaking(vec![change("aaa", "feat!: drop old flag\n\nSemrel-Bump: minor")], "0.2.0")]
+    #[case::skip(vec![change("aaa", "feat: experimental\n\nSemrel-Skip: true"), change("bbb", "fix: typo")], "0.1.1")]
+    #[case::release_as(vec![change("aaa", "chore: prepare\n\nRelease-As: 3.0.0"), change("bbb", "feat: add login")], "3.0.0")]
+    #[case::release_as_behind(vec![change("aaa", "chore: prepare\n\nRelease-As: 0.0.9"), change("bbb", "fix: typo")], "0.1.1")]
+    #[case::release_as_highest(vec![change("aaa", "chore: prepare\n\nRelease-As: 2.0.0"), change("bbb", "chore: prepare\n\nRelease-As: 2.5.0")], "2.5.0")]
+    fn test_trailer_overrides(#[case] changes: Vec<CommitInfo>, #[case] expected_version: &str) {
+        let changelog = ChangeLog::new(SimpleVersion::new(0, 1, 0), changes);
+        assert_eq!(changelog.next_version(&[]).to_string(), expected_version);
+    }
+
+    #[test]
+    fn breaking_changes_are_listed_first() {
+        let changelog = ChangeLog::new(
+            SimpleVersion::new(1, 2, 0),
+            vec![
+                change("aaa", "feat(api)!: drop v1 endpoints\n\nBREAKING CHANGE: `/v1/users` is gone\nBREAKING CHANGE: tokens now expire"),
+                change("bbb", "fix!: reject empty passwords"),
+                change("ccc", "fix: handle timeout"),
+                change("ddd", "feat: redesign\n\nBREAKING CHANGE: the config file moved\nto `~/.config`\n\nRefs: #12"),
+            ],
+        );
+
+        let notes = changelog.release_notes_for(SimpleVersion::new(2, 0, 0), chrono::NaiveDate::default());
+
+        let expected = "## Breaking Changes\n- **api**: `/v1/users` is gone\n- **api**: tokens now expire\n- reject empty passwords\n- the config file moved\n  to `~/.config`\n";
+        assert!(notes.contains(expected), "{notes}");
+        assert!(notes.find("## Breaking Changes") < notes.find("## Features"), "{notes}");
+    }
+
+    #[test]
+    fn configured_types_are_titled_ordered_and_hidden() {
+        let security = CommitType::Custom("security".to_string());
+        let deps = CommitType::Custom("deps".to_string());
+        let types = [
+            TypeDefinition {
+                title: Some("Security".to_string()),
+                order: Some(15),
+                ..TypeDefinition::new(security)
+            },
+            TypeDefinition {
+                hidden: true,
+                ..TypeDefinition::new(deps)
+            },
+            TypeDefinition {
+                title: Some("Documentation changes".to_string()),
+                order: Some(1),
+                ..TypeDefinition::new(CommitType::Docs)
+            },
+        ];
+        let changelog = ChangeLog::new(
+            SimpleVersion::new(0, 1, 0),
+            vec![
+                change("aaa", "deps: bump serde"),
+                change("bbb", "fix: handle timeout"),
+                change("ccc", "security: escape html"),
+                change("ddd", "feat: add login"),
+                change("eee", "docs: readme"),
+                change("fff", "audit: review logs"),
+            ],
+        )
+        .with_types(&types);
+
+        let notes = changelog.release_notes_for(SimpleVersion::new(0, 2, 0), chrono::NaiveDate::default());
+
+        let headings = notes.lines().filter(|line| line.starts_with("## ")).collect::<Vec<_>>();
+        assert_eq!(headings, vec!["## Documentation changes", "## Features", "## Security", "## Fixes", "## audit"]);
+        assert!(!notes.contains("bump serde"), "{notes}");
+    }
+
+    #[test]
+    fn skipped_commits_are_left_out_of_release_notes() {
+        let changelog = ChangeLog::new(SimpleVersion::new(0, 1, 0), vec![change("aaa", "feat: experimental\n\nSemrel-Skip: true"), change("bbb", "fix: typo")]);
+        let effective = changelog.effective_changes().iter().map(|c| c.id.as_str()).collect::<Vec<_>>();
+        let notes = changelog.release_notes_for(SimpleVersion::new(0, 1, 1), chrono::NaiveDate::default());
+
+        assert_eq!(effective, vec!["bbb"]);
+        assert!(notes.contains("- typo"), "{notes}");
+        assert!(!notes.contains("experimental"), "{notes}");
+        assert!(!notes.contains("## Features"), "{notes}");
+    }
+
+    /// 0.1.0 -> feat -> 0.2.0 -> fix, returning the commit ids in that order
+    fn build_two_releases(test_repo: &TestRepo) -> Vec<Oid> {
+        let mut ids = vec![];
+        test_repo
+            .add_file("Cargo.toml", "[package]\nname = \"test\"\nversion = \"0.1.0\"\n")
+            .unwrap();
+        ids.push(test_repo.commit("semrel: 0.1.0").unwrap());
+        test_repo.add_file("a.rs", "fn a() {}").unwrap();
+        ids.push(test_repo.commit("feat: add a").unwrap());
+        test_repo
+            .add_file("Cargo.toml", "[package]\nname = \"test\"\nversion = \"0.2.0\"\n")
+            .unwrap();
+        ids.push(test_repo.commit("semrel: 0.2.0").unwrap());
+        test_repo.add_file("b.rs", "fn b() {}").unwrap();
+        ids.push(test_repo.commit("fix: fix b").unwrap());
+        ids
+    }
+
+    #[test]
+    fn shallow_clone_without_boundary_fails() {
+        let test_repo = TestRepo::new();
+        let ids = build_two_releases(&test_repo);
+        std::fs::write(test_repo.repo.path().join("shallow"), format!("{}\n", ids[3])).unwrap();
+
+        let manifest_path = test_repo.path().join("Cargo.toml");
+        let result = get_changelog(&test_repo.repo, &manifest_path);
+
+        assert_eq!(result.err(), Some(RepositoryError::ShallowRepository(1)));
+    }
+
+    #[test]
+    fn shallow_clone_with_boundary_proceeds() {
+        let test_repo = TestRepo::new();
+        let ids = build_two_releases(&test_repo);
+        std::fs::write(test_repo.repo.path().join("shallow"), format!("{}\n", ids[1])).unwrap();
+
+        let manifest_path = test_repo.path().join("Cargo.toml");
+        let changelog = get_changelog(&test_repo.repo, &manifest_path).unwrap();
+        let messages: Vec<String> = changelog.changes.iter().map(|c| c.commit.message()).collect();
+
+        assert_eq!(messages, vec!["fix: fix b"]);
+    }
+
+    #[test]
+    fn shallow_clone_is_allowed_when_asked() {
+        let test_repo = TestRepo::new();
+        let ids = build_two_releases(&test_repo);
+        std::fs::write(test_repo.repo.path().join("shallow"), format!("{}\n", ids[3])).unwrap();
+        let options = ChangeLogOptions {
+            allow_shallow: true,
+            ..Default::default()
+        };
+
+        let manifest_path = test_repo.path().join("Cargo.toml");
+        let changelog = get_changelog_with(&test_repo.repo, &manifest_path, &options).unwrap();
+
+        assert_eq!(changelog.current_version.to_string(), "0.2.0");
+    }
+
+    #[rstest]
+    #[case::release_range(Some(0), Some(2), "0.1.0", vec!["semrel: 0.2.0", "feat: add a"])]
+    #[case::from_release(Some(2), None, "0.2.0", vec!["fix: fix b"])]
+    #[case::to_old_commit(None, Some(1), "0.1.0", vec!["feat: add a"])]
+    #[case::whole_history(None, None, "0.2.0", vec!["fix: fix b"])]
+    fn test_changelog_range(#[case] from: Option<usize>, #[case] to: Option<usize>, #[case] expected_version: &str, #[case] expected: Vec<&str>) {
+        let test_repo = TestRepo::new();
+        let ids = build_two_releases(&test_repo);
+        let options = ChangeLogOptions {
+            from: from.map(|i| ids[i]),
+            to: to.map(|i| ids[i]),
+            ..Default::default()
+        };
+
+        let manifest_path = test_repo.path().join("Cargo.toml");
+        let changelog = get_changelog_with(&test_repo.repo, &manifest_path, &options).unwrap();
+        let messages: Vec<String> = changelog.changes.iter().map(|c| c.commit.message()).collect();
+
+        assert_eq!(changelog.current_version.to_string(), expected_version);
+        assert_eq!(messages, expected);
+    }
+
+    #[test]
+    fn path_filters_scope_the_project() {
+        let test_repo = TestRepo::new();
+        for dir in ["api/docs", "proto"] {
+            std::fs::create_dir_all(test_repo.path().join(dir)).unwrap();
+        }
+        test_repo
+            .add_file("api/Cargo.toml", "[package]\nname = \"api\"\nversion = \"0.1.0\"\n")
+            .unwrap();
+        test_repo.commit("semrel: 0.1.0").unwrap();
+        test_repo.add_file("api/docs/guide.md", "guide").unwrap();
+        test_repo.commit("fix: typo in guide").unwrap();
+        test_repo.add_file("proto/api.proto", "syntax = \"proto3\";").unwrap();
+        test_repo.commit("feat: add proto message").unwrap();
+        test_repo.add_file("api/lib.rs", "fn api() {}").unwrap();
+        test_repo.commit("fix: api bug").unwrap();
+
+        let manifest_path = test_repo.path().join("api/Cargo.toml");
+        let options = ChangeLogOptions {
+            paths: PathFilters {
+                include: vec!["../proto/**".to_string()],
+                exclude: vec!["docs/**".to_string()],
+            },
+            ..Default::default()
+        };
+        let changelog = get_changelog_with(&test_repo.repo, &manifest_path, &options).unwrap();
+        let messages: Vec<String> = changelog.changes.iter().map(|c| c.commit.message()).collect();
+        assert_eq!(messages, vec!["fix: api bug", "feat: add proto message"]);
+
+        let changelog = get_changelog(&test_repo.repo, &manifest_path).unwrap();
+        let messages: Vec<String> = changelog.changes.iter().map(|c| c.commit.message()).collect();
+        assert_eq!(messages, vec!["fix: api bug", "fix: typo in guide"]);
+    }
 }
@@ -1,6 +1,6 @@
 use std::path::{Path, PathBuf};
 
-use crate::{BumpRule, CommitType, ConventionalCommit, match_rule};
+use crate::{BumpRule, CommitType, ConventionalCommit, ParseOptions, match_rule};
 
 #[derive(Debug, Default, Clone, serde::Deserialize, Ord, PartialOrd, Eq, PartialEq, Hash)]
 pub struct CommitInfo {
@@ -12,6 +12,17 @@ pub struct CommitInfo {
     pub commit: ConventionalCommit,
     // The timestamp of the commit
     pub timestamp: u64,
+    // The merge commit that brought this commit into the mainline, when walking all parents
+    #[serde(default)]
+    pub merged_by: Option<String>,
+    // The author's name and email
+    #[serde(default)]
+    pub author: String,
+    #[serde(default)]
+    pub author_email: String,
+    // Whether the commit has more than one parent
+    #[serde(default)]
+    pub is_merge: bool,
 }
 
 impl CommitInfo {
@@ -21,6 +32,10 @@ impl CommitInfo {
             files: files.into_iter().map(|file| file.into()).collect(),
             commit: commit.into(),
             timestamp,
+            merged_by: None,
+            author: String::new(),
+            author_email: String::new(),
+            is_merge: false,
         }
     }
 
@@ -33,7 +48,19 @@ impl CommitInfo {
         &self.commit.commit_type
     }
 
+    /// The `(token, value)` footers of the commit message, in order
+    pub fn footers(&self) -> &[(String, String)] {
+        &self.commit.footers
+    }
+
+    /// The bump for this commit; `Semrel-Skip` and `Semrel-Bump` trailers take precedence over the type
     pub fn rule(&self, rules: &[(CommitType, BumpRule)]) -> BumpRule {
+        if self.commit.is_skipped() {
+            return BumpRule::NoBump;
+        }
+        if let Some(bump) = self.commit.bump_override() {
+            return bump;
+        }
         if self.commit.is_breaking() {
             return BumpRule::Major;
         }
@@ -53,4 +80,41 @@ impl CommitInfo {
         self.files.push(file.as_ref().to_path_buf());
         self
     }
+
+    /// Splits a squash merge into one [`CommitInfo`] per squashed conventional commit
+    ///
+    /// The expanded entries keep the id, files and timestamp of the squash merge.  Commits
+    /// without squashed conventional commits are returned unchanged.
+    pub fn expand_squashed(self, parsing: &ParseOptions) -> Vec<CommitInfo> {
+        let squashed = self.commit.squashed_commits(parsing);
+        if squashed.is_empty() {
+            return vec![self];
+        }
+        squashed
+            .into_iter()
+            .map(|commit| CommitInfo { commit, ..self.clone() })
+            .collect()
+    }
+
+    pub fn with_author(mut self, name: impl Into<String>, email: impl Into<String>) -> Self {
+        self.author = name.into();
+        self.author_email = email.into();
+        self
+    }
+
+    pub fn with_merge(mut self, is_merge: bool) -> Self {
+        self.is_merge = is_merge;
+        self
+    }
+
+    pub fn with_merged_by(mut self, merge_id: impl Into<String>) -> Self {
+        self.merged_by = Some(merge_id.into());
+        self
+    }
+}
+
+/// A change with `message` and no files, for tests
+#[cfg(test)]
+pub(crate) fn change(id: &str, message: &str) -> CommitInfo {
+    CommitInfo::new(id, Vec::<PathBuf>::new(), ConventionalCommit::new(message).unwrap(), 0)
 }
@@ -1,3 +1,86 @@
+use std::path::{Component, Path, PathBuf};
+
+use crate::RepositoryError;
+
+/// Glob patterns that adjust which changed files attribute a commit to the project
+///
+/// Patterns are relative to the project directory.  `exclude` removes files under the
+/// project directory (e.g. `docs/**`) and `include` adds files outside of it
+/// (e.g. `../proto/**`).
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct PathFilters {
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+}
+
+impl PathFilters {
+    /// No patterns: every file under the project directory belongs to the project
+    pub const fn none() -> Self {
+        Self {
+            include: Vec::new(),
+            exclude: Vec::new(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.include.is_empty() && self.exclude.is_empty()
+    }
+}
+
+/// [`PathFilters`] compiled against the project directory, relative to the repository root
+pub(super) struct ProjectMatcher {
+    project_path: PathBuf,
+    include: Vec<glob::Pattern>,
+    exclude: Vec<glob::Pattern>,
+}
+
+impl ProjectMatcher {
+    const OPTIONS: glob::MatchOptions = glob::MatchOptions {
+        case_sensitive: true,
+        require_literal_separator: true,
+        require_literal_leading_dot: false,
+    };
+
+    pub(super) fn new(project_path: impl Into<PathBuf>, filters: &PathFilters) -> Result<Self, RepositoryError> {
+        let project_path = normalize(&project_path.into());
+        let compile = |patterns: &[String]| {
+            patterns
+                .iter()
+                .map(|pattern| {
+                    let anchored = normalize(&project_path.join(pattern));
+                    glob::Pattern::new(&anchored.to_string_lossy()).map_err(|why| RepositoryError::InvalidPathPattern(pattern.clone(), why.to_string()))
+                })
+                .collect::<Result<Vec<_>, _>>()
+        };
+        let include = compile(&filters.include)?;
+        let exclude = compile(&filters.exclude)?;
+        Ok(Self { project_path, include, exclude })
+    }
+
+    /// Whether a changed file, relative to the repository root, belongs to the project
+    pub(super) fn matches(&self, file: &Path) -> bool {
+        if self.include.iter().any(|p| p.matches_path_with(file, Self::OPTIONS)) {
+            return true;
+        }
+        file.starts_with(&self.project_path) && !self.exclude.iter().any(|p| p.matches_path_with(file, Self::OPTIONS))
+    }
+}
+
+/// Lexically resolves `.` and `..` components
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
 fn first_word_is_hex(s: &str) -> bool {
     let word = s.split_whitespace().next().unwrap_or("");
     !word.is_empty() && word.chars().all(|c| c.is_ascii_hexdigit())
@@ -77,4 +160,32 @@ mod tests {
         let result = prune_message(input);
         assert_eq!(result, expected);
     }
+
+    #[rstest]
+    #[case::in_project("packages/api", &[], &[], "packages/api/src/lib.rs", true)]
+    #[case::outside_project("packages/api", &[], &[], "packages/proto/api.proto", false)]
+    #[case::excluded_docs("packages/api", &[], &["docs/**"], "packages/api/docs/guide.md", false)]
+    #[case::excluded_fixtures("packages/api", &[], &["tests/fixtures/**"], "packages/api/tests/fixtures/data.json", false)]
+    #[case::not_excluded("packages/api", &[], &["docs/**"], "packages/api/src/docs.rs", true)]
+    #[case::included_sibling("packages/api", &["../proto/**"], &[], "packages/proto/api.proto", true)]
+    #[case::included_extension("packages/api", &["../shared/*.rs"], &[], "packages/shared/nested/lib.rs", false)]
+    #[case::root_project("", &[], &["*.md"], "README.md", false)]
+    #[case::root_project_nested("", &[], &["*.md"], "src/README.md", true)]
+    fn test_project_matcher(#[case] project: &str, #[case] include: &[&str], #[case] exclude: &[&str], #[case] file: &str, #[case] expected: bool) {
+        let filters = PathFilters {
+            include: include.iter().map(|s| s.to_string()).collect(),
+            exclude: exclude.iter().map(|s| s.to_string()).collect(),
+        };
+        let matcher = ProjectMatcher::new(project, &filters).unwrap();
+        assert_eq!(matcher.matches(Path::new(file)), expected);
+    }
+
+    #[test]
+    fn invalid_pattern_is_reported() {
+        let filters = PathFilters {
+            include: vec!["src/[".to_string()],
+            exclude: vec![],
+        };
+        assert!(matches!(ProjectMatcher::new("", &filters), Err(RepositoryError::InvalidPathPattern(..))));
+    }
 }
@@ -0,0 +1,159 @@
+use std::path::PathBuf;
+
+use git2::Repository;
+
+use crate::RepositoryError;
+
+/// Identifies hooks written by semrel, so they can be replaced or removed safely
+const HOOK_MARKER: &str = "# Installed by semrel";
+
+/// The git hooks semrel can install
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookKind {
+    /// Rejects the commit when `semrel lint` fails on its message
+    CommitMsg,
+    /// Adds a reminder of the commit message format to new messages
+    PrepareCommitMsg,
+}
+
+impl HookKind {
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            HookKind::CommitMsg => "commit-msg",
+            HookKind::PrepareCommitMsg => "prepare-commit-msg",
+        }
+    }
+
+    fn script(&self) -> String {
+        let body = match self {
+            HookKind::CommitMsg => "exec semrel lint \"$1\"",
+            // $2 is empty when git asks for a brand new message
+            HookKind::PrepareCommitMsg => "if [ -z \"$2\" ]; then\n    printf '\\n# <type>(<scope>): <subject>  (checked by semrel lint)\\n' >> \"$1\"\nfi",
+        };
+        format!("#!/bin/sh\n{HOOK_MARKER}\n{body}\n")
+    }
+}
+
+/// The directory git runs hooks from, honouring `core.hooksPath`
+pub fn hooks_dir(repo: &Repository) -> PathBuf {
+    let configured = repo.config().ok().and_then(|config| config.get_path("core.hooksPath").ok());
+    match configured {
+        // Relative paths are relative to the top of the work tree, like git itself
+        Some(path) if path.is_relative() => repo.workdir().unwrap_or_else(|| repo.path()).join(path),
+        Some(path) => path,
+        None => repo.path().join("hooks"),
+    }
+}
+
+fn is_semrel_hook(contents: &str) -> bool {
+    contents.lines().any(|line| line.trim() == HOOK_MARKER)
+}
+
+/// Writes the hooks, refusing to replace hooks semrel did not write unless `force` is set
+pub fn install_hooks(repo: &Repository, kinds: &[HookKind], force: bool) -> Result<Vec<PathBuf>, RepositoryError> {
+    let dir = hooks_dir(repo);
+    let hook_error = |path: &PathBuf, why: std::io::Error| RepositoryError::HookError(path.clone(), why.to_string());
+    // Check every hook first so nothing is written when one of them is foreign
+    let paths = kinds.iter().map(|kind| dir.join(kind.file_name())).collect::<Vec<_>>();
+    for path in &paths {
+        if let Ok(contents) = std::fs::read_to_string(path) {
+            if !force && !is_semrel_hook(&contents) {
+                return Err(RepositoryError::ForeignHook(path.clone()));
+            }
+        }
+    }
+    std::fs::create_dir_all(&dir).map_err(|why| hook_error(&dir, why))?;
+    for (kind, path) in kinds.iter().zip(&paths) {
+        tracing::debug!("Writing hook: {}", path.display());
+        std::fs::write(path, kind.script()).map_err(|why| hook_error(path, why))?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755)).map_err(|why| hook_error(path, why))?;
+        }
+    }
+    Ok(paths)
+}
+
+/// Removes the hooks semrel installed, refusing to remove other hooks unless `force` is set
+pub fn uninstall_hooks(repo: &Repository, kinds: &[HookKind], force: bool) -> Result<Vec<PathBuf>, RepositoryError> {
+    let dir = hooks_dir(repo);
+    let mut removed = vec![];
+    for kind in kinds {
+        let path = dir.join(kind.file_name());
+        let Ok(contents) = std::fs::read_to_string(&path) else {
+            continue;
+        };
+        if !force && !is_semrel_hook(&contents) {
+            return Err(RepositoryError::ForeignHook(path));
+        }
+        std::fs::remove_file(&path).map_err(|why| RepositoryError::HookError(path.clone(), why.to_string()))?;
+        removed.push(path);
+    }
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::git::test_repo::TestRepo;
+
+    #[test]
+    fn install_writes_executable_hooks() {
+        let test_repo = TestRepo::new();
+        let repo = &test_repo.repo;
+
+        let paths = install_hooks(repo, &[HookKind::CommitMsg, HookKind::PrepareCommitMsg], false).unwrap();
+
+        assert_eq!(paths, vec![repo.path().join("hooks/commit-msg"), repo.path().join("hooks/prepare-commit-msg")]);
+        let script = std::fs::read_to_string(&paths[0]).unwrap();
+        assert!(script.starts_with("#!/bin/sh\n"), "{script}");
+        assert!(script.contains("semrel lint \"$1\""), "{script}");
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(std::fs::metadata(&paths[0]).unwrap().permissions().mode() & 0o777, 0o755);
+        }
+    }
+
+    #[test]
+    fn install_respects_core_hooks_path() {
+        let test_repo = TestRepo::new();
+        let repo = &test_repo.repo;
+        repo.config().unwrap().set_str("core.hooksPath", ".githooks").unwrap();
+
+        let paths = install_hooks(repo, &[HookKind::CommitMsg], false).unwrap();
+
+        assert_eq!(paths, vec![test_repo.path().join(".githooks/commit-msg")]);
+        assert!(paths[0].exists());
+    }
+
+    #[test]
+    fn foreign_hooks_are_kept_unless_forced() {
+        let test_repo = TestRepo::new();
+        let repo = &test_repo.repo;
+        let hook = hooks_dir(repo).join("commit-msg");
+        std::fs::create_dir_all(hook.parent().unwrap()).unwrap();
+        std::fs::write(&hook, "#!/bin/sh\nnpx commitlint --edit \"$1\"\n").unwrap();
+
+        assert_eq!(install_hooks(repo, &[HookKind::CommitMsg], false), Err(RepositoryError::ForeignHook(hook.clone())));
+        assert_eq!(uninstall_hooks(repo, &[HookKind::CommitMsg], false), Err(RepositoryError::ForeignHook(hook.clone())));
+        assert!(std::fs::read_to_string(&hook).unwrap().contains("commitlint"));
+
+        install_hooks(repo, &[HookKind::CommitMsg], true).unwrap();
+        assert!(std::fs::read_to_string(&hook).unwrap().contains("semrel lint"));
+    }
+
+    #[test]
+    fn reinstall_and_uninstall_own_hooks() {
+        let test_repo = TestRepo::new();
+        let repo = &test_repo.repo;
+        install_hooks(repo, &[HookKind::CommitMsg], false).unwrap();
+        install_hooks(repo, &[HookKind::CommitMsg], false).unwrap();
+
+        let removed = uninstall_hooks(repo, &[HookKind::CommitMsg, HookKind::PrepareCommitMsg], false).unwrap();
+
+        assert_eq!(removed, vec![hooks_dir(repo).join("commit-msg")]);
+        assert!(!hooks_dir(repo).join("commit-msg").exists());
+    }
+}
@@ -1,11 +1,27 @@
 mod changelog;
 mod commit_info;
 mod filtering;
+mod hooks;
+mod notes;
+mod preflight;
 mod recent;
+mod release;
 mod repo;
+mod strict;
+mod tag;
+#[cfg(test)]
+pub(crate) mod test_repo;
+mod worktree;
 
-pub use changelog::{ChangeLog, CommitGroup, collect_changelog_commits_streaming, get_changelog, revwalk};
+pub use changelog::{ChangeLog, ChangeLogOptions, CommitGroup, HistoryMode, collect_changelog_commits_streaming, get_changelog, get_changelog_with, revwalk};
 pub use commit_info::CommitInfo;
-pub use filtering::prune_message;
+pub use filtering::{PathFilters, prune_message};
+pub use hooks::{HookKind, hooks_dir, install_hooks, uninstall_hooks};
+pub use notes::{NOTES_REF, amend_message, apply_note_override, read_note_override, write_note_override};
+pub use preflight::preflight;
 pub use recent::get_recent_commit;
-pub use repo::{find_top_of_repo, get_repo, is_repo, top_of_repo};
+pub use release::{BackfillStatus, Release, backfill_tags, find_containing_release, get_release, get_releases, get_version_at, tag_release};
+pub use repo::{commits_in_range, find_top_of_repo, get_repo, is_repo, resolve_commit, signature, top_of_repo};
+pub use strict::{StrictAllowlist, check_strict, strict_violations};
+pub use tag::{create_tag, find_tag_target, format_tag_name, parse_tag_name};
+pub use worktree::{ReleasePlan, WorktreeSnapshot, commit_paths};
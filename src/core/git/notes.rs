@@ -0,0 +1,152 @@
+use git2::{Oid, Repository};
+
+use super::signature;
+use crate::{BumpRule, CommitType, ConventionalCommit, ConventionalCommitError, ParseOptions, RepositoryError};
+
+/// The notes ref holding corrected commit messages
+pub const NOTES_REF: &str = "refs/notes/semrel";
+
+/// Trailers that a note may carry on their own, without replacing the commit message
+const OVERRIDE_TRAILERS: [&str; 3] = ["Semrel-Bump", "Release-As", "Semrel-Skip"];
+
+fn is_override_trailer(line: &str) -> bool {
+    line.split_once(':')
+        .is_some_and(|(key, _)| OVERRIDE_TRAILERS.iter().any(|token| key.trim().eq_ignore_ascii_case(token)))
+}
+
+/// Reads the override note attached to `oid`, if any
+pub fn read_note_override(repo: &Repository, oid: Oid) -> Option<String> {
+    let note = repo.find_note(Some(NOTES_REF), oid).ok()?;
+    let message = note.message()?.trim();
+    match message.is_empty() {
+        true => None,
+        false => Some(message.to_string()),
+    }
+}
+
+/// Applies an override note to a commit message
+///
+/// A note made up only of override trailers (e.g. `Semrel-Bump: none`) is appended to the
+/// original message.  Any other note is a corrected message and replaces the original.
+pub fn apply_note_override(message: &str, note: &str) -> String {
+    let trailers_only = note.lines().filter(|line| !line.trim().is_empty()).all(is_override_trailer);
+    match trailers_only {
+        true => format!("{}\n\n{}", message.trim_end(), note.trim()),
+        false => note.trim().to_string(),
+    }
+}
+
+/// Attaches an override note to `oid`, replacing any existing one
+pub fn write_note_override(repo: &Repository, oid: Oid, message: impl AsRef<str>) -> Result<Oid, RepositoryError> {
+    let sig = signature(repo)?;
+    let message = format!("{}\n", message.as_ref().trim_end());
+    repo.note(&sig, &sig, Some(NOTES_REF), oid, &message, true)
+        .map_err(|why| RepositoryError::NoteError(oid.to_string(), why.message().to_string()))
+}
+
+/// Rewrites a commit message with a corrected type, scope and/or `Semrel-Bump` trailer
+///
+/// The message and type are read with `parsing`, so configured aliases, gitmoji and patterns
+/// apply the same way they do when the changelog is collected.
+pub fn amend_message(message: &str, commit_type: Option<&str>, scope: Option<&str>, bump: Option<BumpRule>, parsing: &ParseOptions) -> Result<String, ConventionalCommitError> {
+    if let Some(bump @ BumpRule::Notset) = bump {
+        return Err(ConventionalCommitError::InvalidBumpOverride(bump));
+    }
+    let mut commit = ConventionalCommit::parse_with(message, parsing)?;
+    if let Some(commit_type) = commit_type {
+        commit.commit_type = match parsing.aliases.resolve(commit_type) {
+            CommitType::Unknown | CommitType::NonCompliant => return Err(ConventionalCommitError::InvalidCommitType(commit_type.to_string())),
+            commit_type => commit_type,
+        };
+    }
+    if let Some(scope) = scope {
+        // A non-compliant message is written back as is, which would drop the scope
+        if commit.commit_type == CommitType::NonCompliant && !scope.is_empty() {
+            return Err(ConventionalCommitError::ScopeWithoutType(scope.to_string()));
+        }
+        commit.scope = Some(scope).filter(|scope| !scope.is_empty()).map(str::to_string);
+    }
+    let mut message = commit.to_string();
+    if let Some(bump) = bump {
+        let kept = message
+            .lines()
+            .filter(|line| {
+                !line
+                    .split_once(':')
+                    .is_some_and(|(key, _)| key.trim().eq_ignore_ascii_case("Semrel-Bump"))
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        message = format!("{}\n\nSemrel-Bump: {bump}", kept.trim_end());
+    }
+    // Make sure the corrected message still parses
+    ConventionalCommit::parse_with(&message, parsing)?;
+    Ok(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::git::test_repo::TestRepo;
+    use rstest::rstest;
+
+    fn init_repo() -> (TestRepo, Oid) {
+        let test_repo = TestRepo::new();
+        let oid = test_repo.commit_file("a.rs", "fn a() {}", "add a");
+        (test_repo, oid)
+    }
+
+    #[rstest]
+    #[case::replaces("feat: add login", "fix: add login", "fix: add login")]
+    #[case::trailer_only("feat: add login\n\nRefs: #1", "Semrel-Bump: none", "feat: add login\n\nRefs: #1\n\nSemrel-Bump: none")]
+    #[case::multiple_trailers("feat: add login", "semrel-skip: true\nRelease-As: 2.0.0", "feat: add login\n\nsemrel-skip: true\nRelease-As: 2.0.0")]
+    #[case::conventional_header("add login", "feat: add login\n\nSemrel-Bump: patch", "feat: add login\n\nSemrel-Bump: patch")]
+    fn test_apply_note_override(#[case] message: &str, #[case] note: &str, #[case] expected: &str) {
+        assert_eq!(apply_note_override(message, note), expected);
+    }
+
+    #[rstest]
+    #[case::set_type("add login", Some("feat"), None, None, "feat: add login")]
+    #[case::change_type("feat(auth): add login", Some("fix"), None, None, "fix(auth): add login")]
+    #[case::set_scope("fix: add login", None, Some("auth"), None, "fix(auth): add login")]
+    #[case::clear_scope("fix(auth): add login", None, Some(""), None, "fix: add login")]
+    #[case::bump("feat: add login\n\nSemrel-Bump: major", None, None, Some(BumpRule::NoBump), "feat: add login\n\nSemrel-Bump: none")]
+    fn test_amend_message(#[case] message: &str, #[case] commit_type: Option<&str>, #[case] scope: Option<&str>, #[case] bump: Option<BumpRule>, #[case] expected: &str) {
+        assert_eq!(amend_message(message, commit_type, scope, bump, &ParseOptions::default()).unwrap(), expected);
+    }
+
+    #[rstest]
+    #[case::alias("add login", Some("bugfix"), None, "fix: add login")]
+    #[case::gitmoji(":sparkles: add login", None, Some("auth"), "feat(auth): add login")]
+    #[case::pattern("[ENG-12] add login", Some("feat"), None, "feat(ENG-12): add login")]
+    fn amend_message_uses_parse_options(#[case] message: &str, #[case] commit_type: Option<&str>, #[case] scope: Option<&str>, #[case] expected: &str) {
+        let parsing = ParseOptions {
+            aliases: crate::CommitTypeAliases::new([("bugfix".to_string(), CommitType::Fix)], []),
+            patterns: vec![crate::CommitPattern::new(r"^\[(?P<scope>[A-Z]+-\d+)\] (?P<subject>.+)$", Some("fix".to_string()), None).unwrap()],
+            gitmoji: Some(crate::Gitmoji::default()),
+        };
+
+        assert_eq!(amend_message(message, commit_type, scope, None, &parsing).unwrap(), expected);
+    }
+
+    #[rstest]
+    #[case::empty_type(Some(""), None, None, ConventionalCommitError::InvalidCommitType("".to_string()))]
+    #[case::scope_without_type(None, Some("auth"), None, ConventionalCommitError::ScopeWithoutType("auth".to_string()))]
+    #[case::notset_bump(Some("feat"), None, Some(BumpRule::Notset), ConventionalCommitError::InvalidBumpOverride(BumpRule::Notset))]
+    fn amend_message_rejects(#[case] commit_type: Option<&str>, #[case] scope: Option<&str>, #[case] bump: Option<BumpRule>, #[case] expected: ConventionalCommitError) {
+        assert_eq!(amend_message("add login", commit_type, scope, bump, &ParseOptions::default()), Err(expected));
+    }
+
+    #[test]
+    fn note_round_trip() {
+        let (test_repo, oid) = init_repo();
+        let repo = &test_repo.repo;
+        assert_eq!(read_note_override(repo, oid), None);
+
+        write_note_override(repo, oid, "feat: add a").unwrap();
+        write_note_override(repo, oid, "fix: add a").unwrap();
+
+        assert_eq!(read_note_override(repo, oid).as_deref(), Some("fix: add a"));
+        assert!(repo.find_note(None, oid).is_err(), "default notes ref should be untouched");
+    }
+}
@@ -0,0 +1,115 @@
+use git2::{Repository, StatusOptions};
+
+use super::{find_tag_target, parse_tag_name};
+use crate::RepositoryError;
+
+/// Checks that the repository is in a state where the manifest may be updated and released
+///
+/// * tracked files have no uncommitted changes in the working tree or index
+/// * HEAD is on a branch, and that branch matches one of `branches` when any are given
+/// * HEAD does not already carry a release tag named with `tag_format`
+pub fn preflight(repo: &Repository, branches: &[String], tag_format: &str) -> Result<(), RepositoryError> {
+    let mut options = StatusOptions::new();
+    options.include_untracked(false).include_ignored(false).exclude_submodules(true);
+    let statuses = repo
+        .statuses(Some(&mut options))
+        .map_err(|why| RepositoryError::InvalidRepository(why.to_string()))?;
+    let dirty = statuses
+        .iter()
+        .filter(|entry| entry.status() != git2::Status::CURRENT)
+        .filter_map(|entry| entry.path().map(String::from))
+        .collect::<Vec<_>>();
+    if !dirty.is_empty() {
+        return Err(RepositoryError::DirtyWorkingTree(dirty.join(", ")));
+    }
+
+    if repo.head_detached().unwrap_or(false) {
+        return Err(RepositoryError::DetachedHead);
+    }
+    let head = repo.head().map_err(|why| RepositoryError::InvalidRepository(why.to_string()))?;
+    let branch = head.shorthand().unwrap_or_default().to_string();
+    if !branches.is_empty() {
+        let allowed = branches.iter().any(|pattern| match glob::Pattern::new(pattern) {
+            Ok(pattern) => pattern.matches(&branch),
+            Err(_) => pattern == &branch,
+        });
+        if !allowed {
+            return Err(RepositoryError::BranchNotAllowed(branch, branches.join(", ")));
+        }
+    }
+
+    let head_id = head
+        .peel_to_commit()
+        .map_err(|why| RepositoryError::InvalidRepository(why.to_string()))?
+        .id();
+    let tag_names = repo
+        .tag_names(None)
+        .map_err(|why| RepositoryError::InvalidRepository(why.to_string()))?;
+    for name in tag_names.iter().flatten() {
+        if parse_tag_name(tag_format, name).is_some() && find_tag_target(repo, name)? == Some(head_id) {
+            return Err(RepositoryError::AlreadyReleased(name.to_string()));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::git::test_repo::TestRepo;
+    use crate::create_tag;
+
+    fn init_repo() -> TestRepo {
+        let test_repo = TestRepo::new();
+        test_repo.repo.set_head("refs/heads/main").unwrap();
+        test_repo.commit_file("Cargo.toml", "[package]\nversion = \"0.1.0\"\n", "semrel: 0.1.0");
+        test_repo
+    }
+
+    fn branches(names: &[&str]) -> Vec<String> {
+        names.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn clean_repository_passes() {
+        let test_repo = init_repo();
+        let repo = &test_repo.repo;
+        std::fs::write(test_repo.path().join("untracked.txt"), "ignored").unwrap();
+        assert_eq!(preflight(repo, &branches(&["main", "release/*"]), "v{version}"), Ok(()));
+    }
+
+    #[test]
+    fn dirty_tree_is_refused() {
+        let test_repo = init_repo();
+        let repo = &test_repo.repo;
+        std::fs::write(test_repo.path().join("Cargo.toml"), "[package]\nversion = \"0.2.0\"\n").unwrap();
+        assert_eq!(preflight(repo, &[], "v{version}"), Err(RepositoryError::DirtyWorkingTree("Cargo.toml".to_string())));
+    }
+
+    #[test]
+    fn detached_head_is_refused() {
+        let test_repo = init_repo();
+        let repo = &test_repo.repo;
+        let head = repo.head().unwrap().target().unwrap();
+        repo.set_head_detached(head).unwrap();
+        assert_eq!(preflight(repo, &[], "v{version}"), Err(RepositoryError::DetachedHead));
+    }
+
+    #[test]
+    fn branch_outside_allowlist_is_refused() {
+        let test_repo = init_repo();
+        let repo = &test_repo.repo;
+        let result = preflight(repo, &branches(&["release/*"]), "v{version}");
+        assert_eq!(result, Err(RepositoryError::BranchNotAllowed("main".to_string(), "release/*".to_string())));
+    }
+
+    #[test]
+    fn tagged_head_is_refused() {
+        let test_repo = init_repo();
+        let repo = &test_repo.repo;
+        let head = repo.head().unwrap().target().unwrap();
+        create_tag(repo, "v0.1.0", head, "notes").unwrap();
+        assert_eq!(preflight(repo, &[], "v{version}"), Err(RepositoryError::AlreadyReleased("v0.1.0".to_string())));
+        assert_eq!(preflight(repo, &[], "release-{version}"), Ok(()));
+    }
+}
@@ -0,0 +1,443 @@
+use std::path::{Path, PathBuf};
+
+use git2::Oid;
+
+use super::changelog::{check_shallow_boundary, collect_commits, collect_merged_commits, grafted_commits, load_commit_info, manifest_version_at, resolve_manifest_path, revwalk_from, version_change};
+use super::{CommitInfo, create_tag, find_tag_target, format_tag_name};
+use crate::{ChangeLog, ChangeLogOptions, HistoryMode, RepositoryError, SimpleVersion};
+
+/// A release recorded in the history: the commit that changed the manifest version
+/// together with the commits that were released by it
+pub struct Release {
+    /// The id of the commit that set the manifest version
+    pub id: String,
+    /// The version set by the release commit
+    pub version: SimpleVersion,
+    /// The timestamp of the release commit
+    pub timestamp: u64,
+    /// The commits released, with `current_version` set to the version before the release
+    pub changelog: ChangeLog,
+}
+
+impl Release {
+    /// The release date, derived from the release commit timestamp
+    pub fn date(&self) -> chrono::NaiveDate {
+        chrono::DateTime::from_timestamp(self.timestamp as i64, 0)
+            .unwrap_or_default()
+            .date_naive()
+    }
+
+    /// Renders the release notes for this release
+    pub fn release_notes(&self) -> String {
+        self.changelog.release_notes_for(self.version, self.date())
+    }
+}
+
+/// Finds the release that contains `rev`
+///
+/// Walks the first-parent history from `rev` back to the closest commit that changed the
+/// manifest version, then collects the commits between that release commit and the
+/// release before it.  With [`HistoryMode::AllParents`] the commits merged into that range
+/// are included as well.
+pub fn get_release(repo: &git2::Repository, manifest_path: impl Into<PathBuf>, rev: Oid, options: &ChangeLogOptions) -> Result<Release, RepositoryError> {
+    let (manifest_path, relative_manifest_path) = resolve_manifest_path(manifest_path)?;
+
+    let mut release = None;
+    for oid_result in revwalk_from(repo, &manifest_path, Some(rev), None, &options.paths)? {
+        let (oid, files_changed) = oid_result?;
+        if !files_changed.iter().any(|f| f == &relative_manifest_path) {
+            continue;
+        }
+        let commit = repo
+            .find_commit(oid)
+            .map_err(|_| RepositoryError::CommitNotFound(oid.to_string()))?;
+        if let Some(version) = version_change(repo, &commit, &relative_manifest_path)? {
+            release = Some((oid, version));
+            break;
+        }
+    }
+    let (release_id, version) = release.ok_or_else(|| RepositoryError::ReleaseNotFound(rev.to_string()))?;
+    tracing::debug!("Found release {version} at {release_id}");
+
+    let release_commit = repo
+        .find_commit(release_id)
+        .map_err(|_| RepositoryError::CommitNotFound(release_id.to_string()))?;
+    let changes = match release_commit.parents().next() {
+        Some(parent) => {
+            let walker = revwalk_from(repo, &manifest_path, Some(parent.id()), None, &options.paths)?;
+            let (changes, boundary_found) = collect_commits(repo, walker, &relative_manifest_path, version, &options.parsing)?;
+            check_shallow_boundary(repo, boundary_found)?;
+            changes
+        }
+        None => vec![],
+    };
+    build_release(repo, &manifest_path, &relative_manifest_path, &release_commit, changes, options)
+}
+
+/// Creates the annotated tag for the release that contains `rev`
+///
+/// The tag goes on the release commit rather than on `rev`, so commits made after a release
+/// never carry its version.  Returns the tag name and the tagged commit.
+pub fn tag_release(repo: &git2::Repository, manifest_path: impl Into<PathBuf>, rev: Oid, tag_format: &str, options: &ChangeLogOptions) -> Result<(String, Oid), RepositoryError> {
+    let release = get_release(repo, manifest_path, rev, options)?;
+    let target = Oid::from_str(&release.id).map_err(|_| RepositoryError::CommitNotFound(release.id.clone()))?;
+    let tag_name = format_tag_name(tag_format, release.version);
+    create_tag(repo, &tag_name, target, release.release_notes())?;
+    Ok((tag_name, target))
+}
+
+/// What `tag backfill` did, or would do, for the tag of one release
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BackfillStatus {
+    /// The tag already points at the release commit
+    Exists,
+    /// The tag points at another commit and is left alone
+    Conflict(Oid),
+    /// The tag would be created, in a dry run
+    Missing,
+    /// The tag was created
+    Created,
+}
+
+/// Tags every release on the first-parent history that has no tag yet, oldest first
+///
+/// Existing tags are never moved.  With `dry_run` nothing is written.  Returns each release's
+/// tag name, release commit and status.
+pub fn backfill_tags(
+    repo: &git2::Repository,
+    manifest_path: impl Into<PathBuf>,
+    tag_format: &str,
+    options: &ChangeLogOptions,
+    dry_run: bool,
+) -> Result<Vec<(String, Oid, BackfillStatus)>, RepositoryError> {
+    let releases = get_releases(repo, manifest_path, options, None)?;
+    let mut tags = vec![];
+    for release in releases.iter().rev() {
+        let tag_name = format_tag_name(tag_format, release.version);
+        let target = Oid::from_str(&release.id).map_err(|_| RepositoryError::CommitNotFound(release.id.clone()))?;
+        let status = match find_tag_target(repo, &tag_name)? {
+            Some(existing) if existing == target => BackfillStatus::Exists,
+            Some(existing) => BackfillStatus::Conflict(existing),
+            None if dry_run => BackfillStatus::Missing,
+            None => {
+                create_tag(repo, &tag_name, target, release.release_notes())?;
+                BackfillStatus::Created
+            }
+        };
+        tags.push((tag_name, target, status));
+    }
+    Ok(tags)
+}
+
+/// Reads the manifest version as recorded at `rev`
+pub fn get_version_at(repo: &git2::Repository, manifest_path: impl Into<PathBuf>, rev: Oid) -> Result<SimpleVersion, RepositoryError> {
+    let (_, relative_manifest_path) = resolve_manifest_path(manifest_path)?;
+    let commit = repo
+        .find_commit(rev)
+        .map_err(|_| RepositoryError::CommitNotFound(rev.to_string()))?;
+    manifest_version_at(repo, &commit, &relative_manifest_path)
+}
+
+/// Finds the first release on the first-parent history of HEAD that contains `commit`
+///
+/// Returns `None` when the commit has not been released yet.
+pub fn find_containing_release(repo: &git2::Repository, manifest_path: impl Into<PathBuf>, commit: Oid, options: &ChangeLogOptions) -> Result<Option<Release>, RepositoryError> {
+    let (manifest_path, relative_manifest_path) = resolve_manifest_path(manifest_path)?;
+
+    // Releases are walked newest first; once one does not contain the commit, no older one can
+    let mut containing = None;
+    for oid_result in revwalk_from(repo, &manifest_path, options.to, None, &options.paths)? {
+        let (oid, files_changed) = oid_result?;
+        if !files_changed.iter().any(|f| f == &relative_manifest_path) {
+            continue;
+        }
+        let release_commit = repo
+            .find_commit(oid)
+            .map_err(|_| RepositoryError::CommitNotFound(oid.to_string()))?;
+        if version_change(repo, &release_commit, &relative_manifest_path)?.is_none() {
+            continue;
+        }
+        let contains = oid == commit
+            || repo
+                .graph_descendant_of(oid, commit)
+                .map_err(|why| RepositoryError::InvalidRepository(why.to_string()))?;
+        if !contains {
+            break;
+        }
+        containing = Some(oid);
+    }
+    containing
+        .map(|oid| get_release(repo, &manifest_path, oid, options))
+        .transpose()
+}
+
+/// Reconstructs every release on the first-parent history, newest first
+///
+/// Each commit that changed the manifest version is a release, and the commits between it
+/// and the release before it are its changes.  Commits after the latest release are not
+/// included.  With `limit`, only the most recent releases are returned.
+pub fn get_releases(repo: &git2::Repository, manifest_path: impl Into<PathBuf>, options: &ChangeLogOptions, limit: Option<usize>) -> Result<Vec<Release>, RepositoryError> {
+    let (manifest_path, relative_manifest_path) = resolve_manifest_path(manifest_path)?;
+    let grafts = grafted_commits(repo);
+
+    let mut releases = vec![];
+    let mut pending: Option<git2::Commit> = None;
+    let mut changes = vec![];
+    for oid_result in revwalk_from(repo, &manifest_path, options.to, options.from, &options.paths)? {
+        if limit.is_some_and(|limit| releases.len() >= limit) {
+            return Ok(releases);
+        }
+        let (oid, files_changed) = oid_result?;
+        if grafts.contains(&oid) {
+            tracing::warn!("Reached shallow graft at {oid}; older releases are not available");
+            return Ok(releases);
+        }
+        let (commit, commit_info) = load_commit_info(repo, oid, files_changed, &options.parsing)?;
+        let is_release = match commit_info.contains(&relative_manifest_path) {
+            true => version_change(repo, &commit, &relative_manifest_path)?.is_some(),
+            false => false,
+        };
+        if !is_release {
+            if pending.is_some() {
+                changes.extend(commit_info.expand_squashed(&options.parsing));
+            }
+            continue;
+        }
+        if let Some(release_commit) = pending.replace(commit) {
+            let changes = std::mem::take(&mut changes);
+            releases.push(build_release(repo, &manifest_path, &relative_manifest_path, &release_commit, changes, options)?);
+        }
+    }
+    if let Some(release_commit) = pending {
+        if limit.is_none_or(|limit| releases.len() < limit) {
+            releases.push(build_release(repo, &manifest_path, &relative_manifest_path, &release_commit, changes, options)?);
+        }
+    }
+    Ok(releases)
+}
+
+/// Builds the release for `release_commit` from the first-parent `changes` since the release before it
+fn build_release(
+    repo: &git2::Repository,
+    manifest_path: &Path,
+    relative_manifest_path: &Path,
+    release_commit: &git2::Commit,
+    changes: Vec<CommitInfo>,
+    options: &ChangeLogOptions,
+) -> Result<Release, RepositoryError> {
+    let version = manifest_version_at(repo, release_commit, relative_manifest_path)?;
+    let previous_version = release_commit
+        .parents()
+        .next()
+        .and_then(|parent| manifest_version_at(repo, &parent, relative_manifest_path).ok())
+        .unwrap_or_default();
+    let changes = match options.history {
+        HistoryMode::FirstParent => changes,
+        HistoryMode::AllParents => collect_merged_commits(repo, manifest_path, changes, None, &options.paths, &options.parsing)?,
+    };
+    tracing::debug!("Found release {version} at {} with {} changes", release_commit.id(), changes.len());
+    Ok(Release {
+        id: release_commit.id().to_string(),
+        version,
+        timestamp: release_commit.time().seconds().max(0) as u64,
+        changelog: ChangeLog::new(previous_version, changes).with_types(&options.types),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::git::test_repo::TestRepo;
+    use rstest::rstest;
+
+    fn cargo_toml(version: &str) -> String {
+        format!("[package]\nname = \"test\"\nversion = \"{version}\"\n")
+    }
+
+    #[test]
+    fn release_collects_commits_since_previous_release() {
+        let test_repo = TestRepo::new();
+        let repo = &test_repo.repo;
+        test_repo.commit_file("Cargo.toml", cargo_toml("0.1.0"), "semrel: 0.1.0");
+        test_repo.commit_file("a.rs", "fn a() {}", "feat: add a");
+        test_repo.commit_file("b.rs", "fn b() {}", "fix: fix b");
+        let release_id = test_repo.commit_file("Cargo.toml", cargo_toml("0.2.0"), "semrel: 0.2.0");
+        let head = test_repo.commit_file("c.rs", "fn c() {}", "fix: after release");
+
+        let manifest_path = test_repo.path().join("Cargo.toml");
+        let release = get_release(repo, &manifest_path, head, &ChangeLogOptions::default()).unwrap();
+        let messages = release.changelog.changes.iter().map(|c| c.message()).collect::<Vec<_>>();
+
+        assert_eq!(release.id, release_id.to_string());
+        assert_eq!(release.version, SimpleVersion::new(0, 2, 0));
+        assert_eq!(release.changelog.current_version, SimpleVersion::new(0, 1, 0));
+        assert_eq!(messages, vec!["fix: fix b", "feat: add a"]);
+        assert!(release.release_notes().starts_with("# Release notes: 0.2.0"));
+    }
+
+    #[test]
+    fn initial_release_has_no_changes() {
+        let test_repo = TestRepo::new();
+        let repo = &test_repo.repo;
+        let release_id = test_repo.commit_file("Cargo.toml", cargo_toml("0.1.0"), "First commit");
+
+        let manifest_path = test_repo.path().join("Cargo.toml");
+        let release = get_release(repo, &manifest_path, release_id, &ChangeLogOptions::default()).unwrap();
+
+        assert_eq!(release.version, SimpleVersion::new(0, 1, 0));
+        assert!(release.changelog.changes.is_empty());
+    }
+
+    fn build_history(test_repo: &TestRepo) {
+        test_repo.commit_file("Cargo.toml", cargo_toml("0.1.0"), "semrel: 0.1.0");
+        test_repo.commit_file("a.rs", "fn a() {}", "feat: add a");
+        test_repo.commit_file("Cargo.toml", cargo_toml("0.2.0"), "semrel: 0.2.0");
+        test_repo.commit_file("b.rs", "fn b() {}", "fix: fix b");
+        test_repo.commit_file("Cargo.toml", cargo_toml("0.2.1"), "semrel: 0.2.1");
+        test_repo.commit_file("c.rs", "fn c() {}", "feat: unreleased");
+    }
+
+    #[rstest]
+    #[case::all(None, vec![("0.2.1", "0.2.0", vec!["fix: fix b"]), ("0.2.0", "0.1.0", vec!["feat: add a"]), ("0.1.0", "0.0.0", vec![])])]
+    #[case::limited(Some(2), vec![("0.2.1", "0.2.0", vec!["fix: fix b"]), ("0.2.0", "0.1.0", vec!["feat: add a"])])]
+    #[case::latest(Some(1), vec![("0.2.1", "0.2.0", vec!["fix: fix b"])])]
+    fn history_lists_every_release(#[case] limit: Option<usize>, #[case] expected: Vec<(&str, &str, Vec<&str>)>) {
+        let test_repo = TestRepo::new();
+        let repo = &test_repo.repo;
+        build_history(&test_repo);
+
+        let manifest_path = test_repo.path().join("Cargo.toml");
+        let releases = get_releases(repo, &manifest_path, &ChangeLogOptions::default(), limit).unwrap();
+        let releases = releases
+            .iter()
+            .map(|r| {
+                let messages = r.changelog.changes.iter().map(|c| c.message()).collect::<Vec<_>>();
+                (r.version.to_string(), r.changelog.current_version.to_string(), messages)
+            })
+            .collect::<Vec<_>>();
+        let expected = expected
+            .into_iter()
+            .map(|(v, p, m)| (v.to_string(), p.to_string(), m.into_iter().map(String::from).collect::<Vec<_>>()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(releases, expected);
+    }
+
+    #[test]
+    fn tag_goes_on_release_commit_when_head_is_ahead() {
+        let test_repo = TestRepo::new();
+        let repo = &test_repo.repo;
+        test_repo.commit_file("Cargo.toml", cargo_toml("0.1.0"), "semrel: 0.1.0");
+        test_repo.commit_file("a.rs", "fn a() {}", "feat: add a");
+        let release_id = test_repo.commit_file("Cargo.toml", cargo_toml("0.2.0"), "semrel: 0.2.0");
+        let head = test_repo.commit_file("b.rs", "fn b() {}", "fix: unreleased");
+
+        let manifest_path = test_repo.path().join("Cargo.toml");
+        let (tag_name, target) = tag_release(repo, &manifest_path, head, "v{version}", &ChangeLogOptions::default()).unwrap();
+
+        assert_eq!(tag_name, "v0.2.0");
+        assert_eq!(target, release_id);
+        assert_eq!(crate::find_tag_target(repo, "v0.2.0").unwrap(), Some(release_id));
+    }
+
+    #[test]
+    fn backfill_tags_two_releases() {
+        let test_repo = TestRepo::new();
+        let repo = &test_repo.repo;
+        let first = test_repo.commit_file("Cargo.toml", cargo_toml("0.1.0"), "semrel: 0.1.0");
+        test_repo.commit_file("a.rs", "fn a() {}", "feat: add a");
+        let second = test_repo.commit_file("Cargo.toml", cargo_toml("0.2.0"), "semrel: 0.2.0");
+        test_repo.commit_file("b.rs", "fn b() {}", "fix: unreleased");
+        let manifest_path = test_repo.path().join("Cargo.toml");
+        let options = ChangeLogOptions::default();
+
+        let planned = backfill_tags(repo, &manifest_path, "v{version}", &options, true).unwrap();
+        assert_eq!(
+            planned,
+            vec![("v0.1.0".to_string(), first, BackfillStatus::Missing), ("v0.2.0".to_string(), second, BackfillStatus::Missing)]
+        );
+        assert_eq!(find_tag_target(repo, "v0.1.0").unwrap(), None);
+
+        let created = backfill_tags(repo, &manifest_path, "v{version}", &options, false).unwrap();
+        assert_eq!(
+            created,
+            vec![("v0.1.0".to_string(), first, BackfillStatus::Created), ("v0.2.0".to_string(), second, BackfillStatus::Created)]
+        );
+        assert_eq!(find_tag_target(repo, "v0.1.0").unwrap(), Some(first));
+        assert_eq!(find_tag_target(repo, "v0.2.0").unwrap(), Some(second));
+
+        let again = backfill_tags(repo, &manifest_path, "v{version}", &options, false).unwrap();
+        assert!(again.iter().all(|(_, _, status)| *status == BackfillStatus::Exists), "{again:?}");
+    }
+
+    #[test]
+    fn backfill_leaves_conflicting_tags_alone() {
+        let test_repo = TestRepo::new();
+        let repo = &test_repo.repo;
+        let release = test_repo.commit_file("Cargo.toml", cargo_toml("0.1.0"), "semrel: 0.1.0");
+        let other = test_repo.commit_file("a.rs", "fn a() {}", "feat: add a");
+        create_tag(repo, "v0.1.0", other, "moved").unwrap();
+
+        let manifest_path = test_repo.path().join("Cargo.toml");
+        let tags = backfill_tags(repo, &manifest_path, "v{version}", &ChangeLogOptions::default(), false).unwrap();
+
+        assert_eq!(tags, vec![("v0.1.0".to_string(), release, BackfillStatus::Conflict(other))]);
+        assert_eq!(find_tag_target(repo, "v0.1.0").unwrap(), Some(other));
+    }
+
+    #[test]
+    fn release_in_shallow_clone_without_boundary_fails() {
+        let test_repo = TestRepo::new();
+        let repo = &test_repo.repo;
+        test_repo.commit_file("Cargo.toml", cargo_toml("0.1.0"), "semrel: 0.1.0");
+        let cut = test_repo.commit_file("a.rs", "fn a() {}", "feat: add a");
+        test_repo.commit_file("b.rs", "fn b() {}", "fix: fix b");
+        let release_id = test_repo.commit_file("Cargo.toml", cargo_toml("0.2.0"), "semrel: 0.2.0");
+        std::fs::write(repo.path().join("shallow"), format!("{cut}\n")).unwrap();
+
+        let manifest_path = test_repo.path().join("Cargo.toml");
+        let result = get_release(repo, &manifest_path, release_id, &ChangeLogOptions::default());
+
+        assert!(matches!(result, Err(RepositoryError::ShallowRepository(_))));
+    }
+
+    #[test]
+    fn version_at_reads_manifest_in_commit() {
+        let test_repo = TestRepo::new();
+        let repo = &test_repo.repo;
+        let first = test_repo.commit_file("Cargo.toml", cargo_toml("0.1.0"), "semrel: 0.1.0");
+        test_repo.commit_file("Cargo.toml", cargo_toml("0.2.0"), "semrel: 0.2.0");
+
+        let manifest_path = test_repo.path().join("Cargo.toml");
+        assert_eq!(get_version_at(repo, &manifest_path, first).unwrap(), SimpleVersion::new(0, 1, 0));
+    }
+
+    #[test]
+    fn containing_release_is_first_release_after_commit() {
+        let test_repo = TestRepo::new();
+        let repo = &test_repo.repo;
+        test_repo.commit_file("Cargo.toml", cargo_toml("0.1.0"), "semrel: 0.1.0");
+        let fix = test_repo.commit_file("a.rs", "fn a() {}", "fix: fix a");
+        test_repo.commit_file("b.rs", "fn b() {}", "feat: add b");
+        let release = test_repo.commit_file("Cargo.toml", cargo_toml("0.2.0"), "semrel: 0.2.0");
+        test_repo.commit_file("Cargo.toml", cargo_toml("0.3.0"), "semrel: 0.3.0");
+        let unreleased = test_repo.commit_file("c.rs", "fn c() {}", "fix: fix c");
+
+        let manifest_path = test_repo.path().join("Cargo.toml");
+        let options = ChangeLogOptions::default();
+        let found = find_containing_release(repo, &manifest_path, fix, &options).unwrap().unwrap();
+        assert_eq!(found.id, release.to_string());
+        assert_eq!(found.version, SimpleVersion::new(0, 2, 0));
+
+        let found = find_containing_release(repo, &manifest_path, release, &options)
+            .unwrap()
+            .unwrap();
+        assert_eq!(found.id, release.to_string());
+
+        assert!(
+            find_containing_release(repo, &manifest_path, unreleased, &options)
+                .unwrap()
+                .is_none()
+        );
+    }
+}
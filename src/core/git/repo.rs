@@ -62,3 +62,42 @@ pub fn get_repo(path: impl AsRef<Path>) -> Result<Repository, RepositoryError> {
     };
     Ok(repo)
 }
+
+/// Resolves a revision (sha, branch, tag, `HEAD~2`, ...) to the commit it points at
+pub fn resolve_commit(repo: &Repository, rev: impl AsRef<str>) -> Result<git2::Oid, RepositoryError> {
+    let rev = rev.as_ref();
+    let object = repo
+        .revparse_single(rev)
+        .map_err(|why| RepositoryError::InvalidRevision(rev.to_string(), why.message().to_string()))?;
+    let commit = object
+        .peel_to_commit()
+        .map_err(|why| RepositoryError::InvalidRevision(rev.to_string(), why.message().to_string()))?;
+    Ok(commit.id())
+}
+
+/// Lists the non-merge commits in a revision range such as `origin/main..HEAD`, newest first
+pub fn commits_in_range(repo: &Repository, range: impl AsRef<str>) -> Result<Vec<git2::Oid>, RepositoryError> {
+    let range = range.as_ref();
+    let invalid = |why: git2::Error| RepositoryError::InvalidRevision(range.to_string(), why.message().to_string());
+    let mut revwalk = repo.revwalk().map_err(invalid)?;
+    revwalk.push_range(range).map_err(invalid)?;
+    let mut commits = vec![];
+    for oid in revwalk {
+        let oid = oid.map_err(invalid)?;
+        let commit = repo
+            .find_commit(oid)
+            .map_err(|_| RepositoryError::CommitNotFound(oid.to_string()))?;
+        if commit.parent_count() > 1 {
+            tracing::debug!("Skipping merge commit: {oid}");
+            continue;
+        }
+        commits.push(oid);
+    }
+    Ok(commits)
+}
+
+/// Returns the identity used for commits and tags written by semrel
+pub fn signature(repo: &Repository) -> Result<git2::Signature<'static>, RepositoryError> {
+    repo.signature()
+        .map_err(|why| RepositoryError::NoSignature(why.message().to_string()))
+}
@@ -0,0 +1,120 @@
+use crate::{BumpRule, ChangeLog, CommitInfo, CommitType, RepositoryError};
+
+/// Commits that strict mode lets through even when they are not conventional commits
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct StrictAllowlist {
+    /// Allow commits with more than one parent, e.g. `Merge pull request #12 from ...`
+    pub merges: bool,
+    /// Author names or emails, matched exactly or as glob patterns, e.g. `dependabot[bot]`
+    pub authors: Vec<glob::Pattern>,
+}
+
+impl StrictAllowlist {
+    pub fn allows(&self, info: &CommitInfo) -> bool {
+        if self.merges && info.is_merge {
+            return true;
+        }
+        self.authors.iter().any(|author| {
+            [&info.author, &info.author_email]
+                .into_iter()
+                .any(|candidate| !candidate.is_empty() && (candidate == author.as_str() || author.matches(candidate)))
+        })
+    }
+}
+
+/// The changes that are not conventional commits, or whose custom type has no bump rule
+///
+/// Commits with a `Semrel-Skip` or `Semrel-Bump` trailer, release commits, types defined in
+/// `[semrel.types]` and commits on the allowlist are not reported.
+pub fn strict_violations<'a>(changelog: &'a ChangeLog, rules: &[(CommitType, BumpRule)], allowlist: &StrictAllowlist) -> Vec<&'a CommitInfo> {
+    changelog
+        .changes
+        .iter()
+        .filter(|info| !info.commit.is_skipped() && info.commit.bump_override().is_none())
+        .filter(|info| match info.commit_type() {
+            CommitType::NonCompliant | CommitType::Unknown => true,
+            CommitType::Custom(name) if name.starts_with("semrel") => false,
+            commit_type @ CommitType::Custom(_) => !rules.iter().any(|(known, _)| known == commit_type) && !changelog.types.iter().any(|definition| definition.commit_type == *commit_type),
+            _ => false,
+        })
+        .filter(|info| !allowlist.allows(info))
+        .collect()
+}
+
+/// Fails with every offending commit id and header when [`strict_violations`] finds any
+pub fn check_strict(changelog: &ChangeLog, rules: &[(CommitType, BumpRule)], allowlist: &StrictAllowlist) -> Result<(), RepositoryError> {
+    let violations = strict_violations(changelog, rules, allowlist);
+    if violations.is_empty() {
+        return Ok(());
+    }
+    let listing = violations
+        .iter()
+        .map(|info| {
+            let id = info.id.chars().take(7).collect::<String>();
+            let message = info.message();
+            format!("  {id} {}", message.lines().next().unwrap_or_default())
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    Err(RepositoryError::NonCompliantCommits(listing))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::git::commit_info::change;
+    use crate::{SimpleVersion, TypeDefinition};
+    use rstest::rstest;
+
+    fn rules() -> Vec<(CommitType, BumpRule)> {
+        crate::build_default_rules().collect()
+    }
+
+    #[rstest]
+    #[case::conventional("feat: add login", false)]
+    #[case::non_compliant("added login", true)]
+    #[case::custom_without_rule("deps: bump serde", true)]
+    #[case::custom_with_type("security: pin tls", false)]
+    #[case::skipped("added login\n\nSemrel-Skip: true", false)]
+    #[case::release("semrel: 1.2.3", false)]
+    fn finds_violations(#[case] message: &str, #[case] expected: bool) {
+        let changelog = ChangeLog::new(SimpleVersion::new(1, 0, 0), [change("abc1234def", message)]).with_types(&[TypeDefinition::new(CommitType::Custom("security".to_string()))]);
+
+        let violations = strict_violations(&changelog, &rules(), &StrictAllowlist::default());
+
+        assert_eq!(!violations.is_empty(), expected, "{violations:#?}");
+    }
+
+    #[rstest]
+    #[case::merge(change("a", "Merge pull request #12 from org/branch").with_merge(true), true)]
+    #[case::merge_not_allowed(change("a", "Merge branch 'main'").with_merge(false), false)]
+    #[case::bot_name(change("a", "Bump serde").with_author("dependabot[bot]", "49699333+dependabot[bot]@users.noreply.github.com"), true)]
+    #[case::bot_email(change("a", "Bump serde").with_author("Renovate", "bot@renovateapp.com"), true)]
+    #[case::person(change("a", "Bump serde").with_author("Jane", "jane@example.com"), false)]
+    fn allowlist(#[case] info: CommitInfo, #[case] expected: bool) {
+        let allowlist = StrictAllowlist {
+            merges: true,
+            authors: vec![glob::Pattern::new("dependabot[bot]").unwrap(), glob::Pattern::new("*@renovateapp.com").unwrap()],
+        };
+
+        assert_eq!(allowlist.allows(&info), expected);
+    }
+
+    #[test]
+    fn lists_each_offending_commit() {
+        let changelog = ChangeLog::new(
+            SimpleVersion::new(1, 0, 0),
+            [
+                change("1111111aaaa", "feat: add login"),
+                change("2222222bbbb", "added logout\n\nwith a body"),
+                change("3333333cccc", "wip"),
+            ],
+        );
+
+        let error = check_strict(&changelog, &rules(), &StrictAllowlist::default()).unwrap_err();
+
+        let message = error.to_string();
+        assert!(message.contains("  2222222 added logout\n  3333333 wip"), "{message}");
+        assert!(!message.contains("1111111"), "{message}");
+    }
+}
@@ -0,0 +1,148 @@
+use git2::{Oid, Repository};
+
+use super::signature;
+use crate::{RepositoryError, SimpleVersion};
+
+/// Renders a tag name from a template
+///
+/// Supported placeholders are `{version}`, `{major}`, `{minor}` and `{patch}`.
+pub fn format_tag_name(format: impl AsRef<str>, version: SimpleVersion) -> String {
+    format
+        .as_ref()
+        .replace("{version}", &version.to_string())
+        .replace("{major}", &version.major().to_string())
+        .replace("{minor}", &version.minor().to_string())
+        .replace("{patch}", &version.patch().to_string())
+}
+
+/// Recovers the version from a tag name rendered with `format`
+///
+/// Returns `None` when the tag does not follow the format.
+pub fn parse_tag_name(format: impl AsRef<str>, tag: impl AsRef<str>) -> Option<SimpleVersion> {
+    let mut rest = format.as_ref();
+    let mut tag = tag.as_ref();
+    let mut version = None;
+    let mut parts = [0; 3];
+    while !rest.is_empty() {
+        let placeholder = ["{version}", "{major}", "{minor}", "{patch}"]
+            .into_iter()
+            .find(|p| rest.starts_with(p));
+        let Some(placeholder) = placeholder else {
+            let c = rest.chars().next()?;
+            tag = tag.strip_prefix(c)?;
+            rest = &rest[c.len_utf8()..];
+            continue;
+        };
+        rest = &rest[placeholder.len()..];
+        let allowed = |c: char| c.is_ascii_digit() || (placeholder == "{version}" && c == '.');
+        let end = tag.find(|c: char| !allowed(c)).unwrap_or(tag.len());
+        let (value, remainder) = tag.split_at(end);
+        tag = remainder;
+        match placeholder {
+            "{version}" => version = Some(value.parse::<SimpleVersion>().ok()?),
+            "{major}" => parts[0] = value.parse().ok()?,
+            "{minor}" => parts[1] = value.parse().ok()?,
+            _ => parts[2] = value.parse().ok()?,
+        }
+    }
+    match tag.is_empty() {
+        true => Some(version.unwrap_or(SimpleVersion::new(parts[0], parts[1], parts[2]))),
+        false => None,
+    }
+}
+
+/// Returns the commit an existing tag points at, if the tag exists
+pub fn find_tag_target(repo: &Repository, name: impl AsRef<str>) -> Result<Option<Oid>, RepositoryError> {
+    let name = name.as_ref();
+    let reference = match repo.find_reference(&format!("refs/tags/{name}")) {
+        Ok(reference) => reference,
+        Err(why) if why.code() == git2::ErrorCode::NotFound => return Ok(None),
+        Err(why) => return Err(RepositoryError::TagError(name.to_string(), why.message().to_string())),
+    };
+    let commit = reference
+        .peel_to_commit()
+        .map_err(|why| RepositoryError::TagError(name.to_string(), why.message().to_string()))?;
+    Ok(Some(commit.id()))
+}
+
+/// Creates an annotated tag on `target`
+///
+/// Refuses to touch an existing tag: a tag that already points at `target` is reported as
+/// [`RepositoryError::TagExists`] and one that points elsewhere as [`RepositoryError::TagMismatch`].
+pub fn create_tag(repo: &Repository, name: impl AsRef<str>, target: Oid, message: impl AsRef<str>) -> Result<Oid, RepositoryError> {
+    let name = name.as_ref();
+    match find_tag_target(repo, name)? {
+        Some(existing) if existing == target => return Err(RepositoryError::TagExists(name.to_string())),
+        Some(existing) => return Err(RepositoryError::TagMismatch(name.to_string(), existing.to_string(), target.to_string())),
+        None => {}
+    }
+    let object = repo
+        .find_object(target, Some(git2::ObjectType::Commit))
+        .map_err(|_| RepositoryError::CommitNotFound(target.to_string()))?;
+    let tagger = signature(repo)?;
+    let tag_id = repo
+        .tag(name, &object, &tagger, message.as_ref(), false)
+        .map_err(|why| RepositoryError::TagError(name.to_string(), why.message().to_string()))?;
+    tracing::debug!("Created tag {name} ({tag_id}) on {target}");
+    Ok(tag_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::git::test_repo::TestRepo;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::prefixed("v{version}", "v1.2.3")]
+    #[case::bare("{version}", "1.2.3")]
+    #[case::parts("release-{major}.{minor}", "release-1.2")]
+    #[case::package("semrel@{major}.{minor}.{patch}", "semrel@1.2.3")]
+    fn test_format_tag_name(#[case] format: &str, #[case] expected: &str) {
+        assert_eq!(format_tag_name(format, SimpleVersion::new(1, 2, 3)), expected);
+    }
+
+    #[rstest]
+    #[case::prefixed("v{version}", "v1.2.3", Some(SimpleVersion::new(1, 2, 3)))]
+    #[case::bare("{version}", "1.2.3", Some(SimpleVersion::new(1, 2, 3)))]
+    #[case::package("semrel@{major}.{minor}.{patch}", "semrel@1.2.3", Some(SimpleVersion::new(1, 2, 3)))]
+    #[case::wrong_prefix("v{version}", "release-1.2.3", None)]
+    #[case::not_a_version("v{version}", "vnext", None)]
+    #[case::trailing("v{version}", "v1.2.3-rc1", None)]
+    fn test_parse_tag_name(#[case] format: &str, #[case] tag: &str, #[case] expected: Option<SimpleVersion>) {
+        assert_eq!(parse_tag_name(format, tag), expected);
+    }
+
+    #[test]
+    fn creates_annotated_tag() {
+        let test_repo = TestRepo::new();
+        let repo = &test_repo.repo;
+        let target = test_repo.commit("feat: initial").unwrap();
+        let tag_id = create_tag(repo, "v0.1.0", target, "# Release notes").unwrap();
+        let tag = repo.find_tag(tag_id).unwrap();
+        assert_eq!(tag.target_id(), target);
+        assert_eq!(tag.message(), Some("# Release notes"));
+        assert_eq!(find_tag_target(repo, "v0.1.0").unwrap(), Some(target));
+    }
+
+    #[test]
+    fn refuses_existing_tag() {
+        let test_repo = TestRepo::new();
+        let repo = &test_repo.repo;
+        let target = test_repo.commit("feat: initial").unwrap();
+        create_tag(repo, "v0.1.0", target, "notes").unwrap();
+        let result = create_tag(repo, "v0.1.0", target, "notes");
+        assert_eq!(result, Err(RepositoryError::TagExists("v0.1.0".to_string())));
+    }
+
+    #[test]
+    fn refuses_tag_pointing_elsewhere() {
+        let test_repo = TestRepo::new();
+        let repo = &test_repo.repo;
+        let first = test_repo.commit("feat: initial").unwrap();
+        create_tag(repo, "v0.1.0", first, "notes").unwrap();
+        let second = test_repo.commit("fix: follow up").unwrap();
+        let result = create_tag(repo, "v0.1.0", second, "notes");
+        assert_eq!(result, Err(RepositoryError::TagMismatch("v0.1.0".to_string(), first.to_string(), second.to_string())));
+    }
+}
@@ -0,0 +1,165 @@
+use std::path::{Path, PathBuf};
+
+use git2::{Oid, Repository, Signature};
+use tempfile::TempDir;
+
+use crate::RepositoryError;
+
+/// A repository in a temporary directory, with a committer identity configured
+pub(crate) struct TestRepo {
+    temp_dir: TempDir,
+    pub(crate) repo: Repository,
+}
+
+impl TestRepo {
+    pub(crate) fn new() -> Self {
+        // Default behavior is to setup tracing to error if the RUST_LOG variable is not set
+        //  This code will only setup tracing if the RUST_LOG variable is set
+        if std::env::var("RUST_LOG").is_ok() {
+            tracing_subscriber::fmt()
+                .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+                .try_init()
+                .ok();
+        }
+        let temp_dir = TempDir::new().unwrap();
+        let temp_path = temp_dir.path().join("repo");
+        let repo = Repository::init(&temp_path).unwrap();
+        let mut config = repo.config().unwrap();
+        config.set_str("user.name", "Test").unwrap();
+        config.set_str("user.email", "test@example.com").unwrap();
+        println!("Created repository here: {}", &temp_path.canonicalize().unwrap().display());
+        TestRepo { temp_dir, repo }
+    }
+
+    pub(crate) fn path(&self) -> PathBuf {
+        self.temp_dir.path().join("repo")
+    }
+
+    pub(crate) fn commit(&self, message: &str) -> Result<Oid, RepositoryError> {
+        let sig = Signature::now("Test", "test@example.com").unwrap();
+        let tree_id = {
+            let mut index = self
+                .repo
+                .index()
+                .map_err(|_| RepositoryError::InvalidRepositoryPath(self.path().to_path_buf()))?;
+            index
+                .write_tree()
+                .map_err(|_| RepositoryError::InvalidRepositoryPath(self.path().to_path_buf()))?
+        };
+        let tree = self
+            .repo
+            .find_tree(tree_id)
+            .map_err(|_| RepositoryError::InvalidRepositoryPath(self.path().to_path_buf()))?;
+        let parent_commit = self
+            .repo
+            .head()
+            .ok()
+            .and_then(|h| h.target())
+            .and_then(|t| self.repo.find_commit(t).ok());
+        let parents = parent_commit.as_ref().map(|p| vec![p]).unwrap_or_default();
+        self.repo
+            .commit(Some("HEAD"), &sig, &sig, message, &tree, &parents)
+            .map_err(|_| RepositoryError::InvalidRepositoryPath(self.path().to_path_buf()))
+    }
+
+    /// Commits the current index with HEAD and `other` as parents
+    pub(crate) fn merge(&self, message: &str, other: Oid) -> Oid {
+        let sig = Signature::now("Test", "test@example.com").unwrap();
+        let tree_id = self.repo.index().unwrap().write_tree().unwrap();
+        let tree = self.repo.find_tree(tree_id).unwrap();
+        let head = self.repo.head().unwrap().peel_to_commit().unwrap();
+        let other = self.repo.find_commit(other).unwrap();
+        self.repo
+            .commit(Some("HEAD"), &sig, &sig, message, &tree, &[&head, &other])
+            .unwrap()
+    }
+
+    /// Moves the current branch and the index to `oid`, leaving the work directory alone
+    pub(crate) fn reset(&self, oid: Oid) {
+        let commit = self.repo.find_commit(oid).unwrap();
+        let mut index = self.repo.index().unwrap();
+        index.read_tree(&commit.tree().unwrap()).unwrap();
+        index.write().unwrap();
+        self.repo.head().unwrap().set_target(oid, "test reset").unwrap();
+    }
+
+    /// Writes `path`, stages it and commits it
+    pub(crate) fn commit_file(&self, path: impl AsRef<Path>, content: impl AsRef<str>, message: &str) -> Oid {
+        self.add_file(path, content).unwrap();
+        self.commit(message).unwrap()
+    }
+
+    pub(crate) fn add_file(&self, path: impl AsRef<Path>, content: impl AsRef<str>) -> Result<(), RepositoryError> {
+        use std::fs::File;
+        use std::io::Write;
+        let file_path = self.path().join(path.as_ref());
+        let mut file = File::create(&file_path).unwrap();
+        file.write_all(content.as_ref().as_bytes()).unwrap();
+
+        let mut index = self.repo.index().unwrap();
+        index.add_path(path.as_ref()).unwrap();
+        index.write_tree().unwrap();
+
+        Ok(())
+    }
+
+    #[allow(dead_code)]
+    pub(crate) fn branch(&self, name: impl AsRef<str>) -> Result<(), RepositoryError> {
+        let branch_name = name.as_ref();
+        println!("Switching to branch '{}'", branch_name); // Debug message
+        match self.repo.find_branch(branch_name, git2::BranchType::Local) {
+            Ok(_) => {
+                // Branch exists, so check it out
+                let obj = self.repo.revparse_single(&format!("refs/heads/{}", branch_name)).unwrap();
+                let mut checkout_builder = git2::build::CheckoutBuilder::new();
+                checkout_builder.force();
+                match self.repo.checkout_tree(&obj, None) {
+                    Ok(_) => {
+                        self.repo.set_head(&format!("refs/heads/{}", branch_name)).unwrap();
+                        println!("Switched to existing branch '{}'", branch_name);
+                    }
+                    Err(why) => {
+                        println!("Failed to checkout tree: {why}");
+                        checkout_builder.remove_untracked(true);
+                        match self.repo.checkout_tree(&obj, Some(&mut checkout_builder)) {
+                            Ok(_) => {
+                                self.repo.set_head(&format!("refs/heads/{}", branch_name)).unwrap();
+                                println!("Switched to existing branch '{}'", branch_name);
+                            }
+                            Err(why) => {
+                                println!("Failed to checkout tree: {why}");
+                                self.repo.set_head(&format!("refs/heads/{}", branch_name)).unwrap();
+                                println!("Switched to existing branch '{}'", branch_name);
+                            }
+                        }
+                    }
+                }
+                self.repo.set_head(&format!("refs/heads/{}", branch_name)).unwrap();
+                println!("Switched to existing branch '{}'", branch_name);
+            }
+            Err(_) => {
+                // Branch does not exist, so create it
+                let head_ref = self.repo.head().unwrap();
+                let head_commit = head_ref.peel_to_commit().unwrap();
+                let _branch = self.repo.branch(branch_name, &head_commit, false).unwrap();
+
+                // Checkout the newly created branch
+                let obj = self.repo.revparse_single(&format!("refs/heads/{}", branch_name)).unwrap();
+                let mut checkout_builder = git2::build::CheckoutBuilder::new();
+                checkout_builder.force(); // Force checkout to overcome conflicts
+                match self.repo.checkout_tree(&obj, Some(&mut checkout_builder)) {
+                    Ok(_) => println!("Created and switched to new branch '{}'", branch_name),
+                    Err(e) => {
+                        println!("Failed to checkout tree: {e}");
+                        checkout_builder.remove_untracked(true);
+                        self.repo.set_head(&format!("refs/heads/{}", branch_name)).unwrap();
+                        println!("Switched to existing branch '{}'", branch_name);
+                    }
+                }
+                self.repo.set_head(&format!("refs/heads/{}", branch_name)).unwrap();
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,301 @@
+use std::path::{Path, PathBuf};
+
+use git2::{Oid, Repository};
+
+use super::{create_tag, signature};
+use crate::{RepositoryError, SimpleVersion, SupportedManifest};
+
+/// Converts a path on disk into a path relative to the repository work directory
+fn workdir_relative(repo: &Repository, path: impl AsRef<Path>) -> Result<PathBuf, RepositoryError> {
+    let path = path.as_ref();
+    let workdir = repo
+        .workdir()
+        .ok_or_else(|| RepositoryError::InvalidRepository("repository has no work directory".to_string()))?;
+    let workdir = workdir.canonicalize().unwrap_or_else(|_| workdir.to_path_buf());
+    let canonical = path
+        .canonicalize()
+        .map_err(|_| RepositoryError::CouldNotReadFile(path.to_path_buf()))?;
+    canonical
+        .strip_prefix(&workdir)
+        .map(|p| p.to_path_buf())
+        .map_err(|_| RepositoryError::InvalidRepositoryPath(path.to_path_buf()))
+}
+
+/// Stages `paths` and commits them on top of HEAD
+///
+/// Only `paths` change in the commit; anything else already staged stays staged and is left out.
+pub fn commit_paths(repo: &Repository, paths: &[impl AsRef<Path>], message: impl AsRef<str>) -> Result<Oid, RepositoryError> {
+    let mut index = repo
+        .index()
+        .map_err(|why| RepositoryError::InvalidRepository(why.to_string()))?;
+    let parent = repo.head().ok().and_then(|head| head.peel_to_commit().ok());
+    let mut commit_index = git2::Index::new().map_err(|why| RepositoryError::CommitTreeError(why.to_string()))?;
+    if let Some(parent) = &parent {
+        let tree = parent.tree().map_err(|why| RepositoryError::CommitTreeError(why.to_string()))?;
+        commit_index
+            .read_tree(&tree)
+            .map_err(|why| RepositoryError::CommitTreeError(why.to_string()))?;
+    }
+    for path in paths {
+        let relative = workdir_relative(repo, path)?;
+        tracing::debug!("Staging: {}", relative.display());
+        index
+            .add_path(&relative)
+            .map_err(|why| RepositoryError::FileNotFound(relative.display().to_string(), why.to_string()))?;
+        let entry = index
+            .get_path(&relative, 0)
+            .ok_or_else(|| RepositoryError::FileNotFound(relative.display().to_string(), "not staged".to_string()))?;
+        commit_index
+            .add(&entry)
+            .map_err(|why| RepositoryError::CommitTreeError(why.to_string()))?;
+    }
+    index
+        .write()
+        .map_err(|why| RepositoryError::InvalidRepository(why.to_string()))?;
+    let tree_id = commit_index
+        .write_tree_to(repo)
+        .map_err(|why| RepositoryError::CommitTreeError(why.to_string()))?;
+    let tree = repo
+        .find_tree(tree_id)
+        .map_err(|why| RepositoryError::CommitTreeError(why.to_string()))?;
+    let parents = parent.iter().collect::<Vec<_>>();
+    let sig = signature(repo)?;
+    let oid = repo
+        .commit(Some("HEAD"), &sig, &sig, message.as_ref(), &tree, &parents)
+        .map_err(|why| RepositoryError::InvalidCommit(why.to_string()))?;
+    tracing::debug!("Created commit: {oid}");
+    Ok(oid)
+}
+
+/// The state of HEAD, the index and a set of files, captured so a multi-step write can be undone
+pub struct WorktreeSnapshot {
+    head: Option<Oid>,
+    index_tree: Option<Oid>,
+    files: Vec<(PathBuf, Option<Vec<u8>>)>,
+}
+
+impl WorktreeSnapshot {
+    pub fn capture(repo: &Repository, files: &[impl AsRef<Path>]) -> Self {
+        let head = repo.head().ok().and_then(|head| head.target());
+        let index_tree = repo
+            .index()
+            .and_then(|mut index| index.write_tree())
+            .map_err(|why| tracing::warn!("Could not snapshot index: {why}"))
+            .ok();
+        let files = files
+            .iter()
+            .map(|path| (path.as_ref().to_path_buf(), std::fs::read(path.as_ref()).ok()))
+            .collect();
+        Self { head, index_tree, files }
+    }
+
+    /// Puts HEAD, the index and the captured files back the way they were
+    pub fn restore(&self, repo: &Repository) -> Result<(), RepositoryError> {
+        for (path, contents) in &self.files {
+            let result = match contents {
+                Some(contents) => std::fs::write(path, contents),
+                None if path.exists() => std::fs::remove_file(path),
+                None => Ok(()),
+            };
+            result.map_err(|_| RepositoryError::CouldNotReadFile(path.clone()))?;
+        }
+        if let Some(tree_id) = self.index_tree {
+            let tree = repo
+                .find_tree(tree_id)
+                .map_err(|why| RepositoryError::CommitTreeError(why.to_string()))?;
+            let mut index = repo
+                .index()
+                .map_err(|why| RepositoryError::InvalidRepository(why.to_string()))?;
+            index
+                .read_tree(&tree)
+                .and_then(|_| index.write())
+                .map_err(|why| RepositoryError::InvalidRepository(why.to_string()))?;
+        }
+        if let (Some(head), Ok(mut reference)) = (self.head, repo.head()) {
+            if reference.target() != Some(head) {
+                tracing::debug!("Resetting HEAD to {head}");
+                reference
+                    .set_target(head, "semrel: roll back release")
+                    .map_err(|why| RepositoryError::InvalidRepository(why.to_string()))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The writes that make up a release: the manifest version, the changelog, the commit and the tag
+pub struct ReleasePlan<'a> {
+    pub manifest_path: &'a Path,
+    pub version: SimpleVersion,
+    /// A changelog file to prepend the release notes to
+    pub changelog: Option<&'a Path>,
+    /// Other files to include in the release commit, e.g. a lock file
+    pub add: &'a [PathBuf],
+    pub message: &'a str,
+    pub tag_name: &'a str,
+    pub notes: &'a str,
+}
+
+impl ReleasePlan<'_> {
+    /// Performs every write, or none of them
+    ///
+    /// When a step fails, HEAD, the index and the written files are restored before the error is
+    /// returned.  Returns the release commit.
+    pub fn execute(&self, repo: &Repository) -> Result<Oid, RepositoryError> {
+        let files = std::iter::once(self.manifest_path.to_path_buf())
+            .chain(self.changelog.map(Path::to_path_buf))
+            .chain(self.add.iter().cloned())
+            .collect::<Vec<_>>();
+        let snapshot = WorktreeSnapshot::capture(repo, &files);
+        let result = self.write(repo, &files);
+        if let Err(why) = &result {
+            tracing::error!("Release failed, restoring working tree: {why}");
+            snapshot.restore(repo)?;
+        }
+        result
+    }
+
+    fn write(&self, repo: &Repository, files: &[PathBuf]) -> Result<Oid, RepositoryError> {
+        let data = std::fs::read_to_string(self.manifest_path).map_err(|_| RepositoryError::CouldNotReadFile(self.manifest_path.to_path_buf()))?;
+        let mut manifest = SupportedManifest::parse(self.manifest_path, data)?;
+        manifest.set_version(self.version)?;
+        manifest.write(self.manifest_path)?;
+        if let Some(path) = self.changelog {
+            let existing = std::fs::read_to_string(path).unwrap_or_default();
+            let contents = match existing.is_empty() {
+                true => format!("{}\n", self.notes),
+                false => format!("{}\n\n{existing}", self.notes),
+            };
+            std::fs::write(path, contents).map_err(|why| RepositoryError::CouldNotWriteFile(path.to_path_buf(), why.to_string()))?;
+        }
+        let commit = commit_paths(repo, files, self.message)?;
+        create_tag(repo, self.tag_name, commit, self.notes)?;
+        Ok(commit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::git::test_repo::TestRepo;
+
+    #[test]
+    fn commit_paths_leaves_other_staged_changes_out() {
+        let test_repo = TestRepo::new();
+        let repo = &test_repo.repo;
+        let manifest = test_repo.path().join("Cargo.toml");
+        let other = test_repo.path().join("other.txt");
+        std::fs::write(&manifest, "[package]\nversion = \"0.1.0\"\n").unwrap();
+        std::fs::write(&other, "before").unwrap();
+        commit_paths(repo, &[&manifest, &other], "semrel: 0.1.0").unwrap();
+        std::fs::write(&manifest, "[package]\nversion = \"0.2.0\"\n").unwrap();
+        std::fs::write(&other, "staged").unwrap();
+        std::fs::write(test_repo.path().join("new.txt"), "staged").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("other.txt")).unwrap();
+        index.add_path(Path::new("new.txt")).unwrap();
+        index.write().unwrap();
+
+        let oid = commit_paths(repo, &[&manifest], "semrel: 0.2.0").unwrap();
+        let commit = repo.find_commit(oid).unwrap();
+        let tree = commit.tree().unwrap();
+        let blob = |path: &str| {
+            let entry = tree.get_path(Path::new(path)).unwrap();
+            repo.find_blob(entry.id()).unwrap().content().to_vec()
+        };
+
+        assert_eq!(commit.message(), Some("semrel: 0.2.0"));
+        assert_eq!(blob("Cargo.toml"), b"[package]\nversion = \"0.2.0\"\n");
+        assert_eq!(blob("other.txt"), b"before");
+        assert!(tree.get_path(Path::new("new.txt")).is_err());
+        let statuses = repo.statuses(None).unwrap();
+        let staged = statuses
+            .iter()
+            .filter(|entry| {
+                entry
+                    .status()
+                    .intersects(git2::Status::INDEX_NEW | git2::Status::INDEX_MODIFIED)
+            })
+            .filter_map(|entry| entry.path().map(String::from))
+            .collect::<Vec<_>>();
+        assert_eq!(staged, vec!["new.txt", "other.txt"]);
+    }
+
+    #[test]
+    fn restore_undoes_files_index_and_head() {
+        let test_repo = TestRepo::new();
+        let repo = &test_repo.repo;
+        let manifest = test_repo.path().join("Cargo.toml");
+        let changelog = test_repo.path().join("CHANGELOG.md");
+        std::fs::write(&manifest, "[package]\nversion = \"0.1.0\"\n").unwrap();
+        let initial = commit_paths(repo, &[&manifest], "semrel: 0.1.0").unwrap();
+
+        let snapshot = WorktreeSnapshot::capture(repo, &[&manifest, &changelog]);
+        std::fs::write(&manifest, "[package]\nversion = \"0.2.0\"\n").unwrap();
+        std::fs::write(&changelog, "# Release notes").unwrap();
+        commit_paths(repo, &[&manifest, &changelog], "semrel: 0.2.0").unwrap();
+        snapshot.restore(repo).unwrap();
+
+        assert_eq!(repo.head().unwrap().target(), Some(initial));
+        assert_eq!(std::fs::read_to_string(&manifest).unwrap(), "[package]\nversion = \"0.1.0\"\n");
+        assert!(!changelog.exists());
+        let index = repo.index().unwrap();
+        assert!(index.get_path(Path::new("CHANGELOG.md"), 0).is_none());
+    }
+
+    #[test]
+    fn failed_release_is_rolled_back() {
+        let test_repo = TestRepo::new();
+        let repo = &test_repo.repo;
+        let manifest = test_repo.path().join("Cargo.toml");
+        let changelog = test_repo.path().join("CHANGELOG.md");
+        std::fs::write(&manifest, "[package]\nname = \"test\"\nversion = \"0.1.0\"\n").unwrap();
+        let initial = commit_paths(repo, &[&manifest], "semrel: 0.1.0").unwrap();
+        // The tag already exists on another commit, so the last step of the release fails
+        create_tag(repo, "v0.2.0", initial, "taken").unwrap();
+
+        let plan = ReleasePlan {
+            manifest_path: &manifest,
+            version: SimpleVersion::new(0, 2, 0),
+            changelog: Some(&changelog),
+            add: &[],
+            message: "semrel: 0.2.0",
+            tag_name: "v0.2.0",
+            notes: "# Release notes: 0.2.0",
+        };
+        let error = plan.execute(repo).unwrap_err();
+
+        assert!(matches!(error, RepositoryError::TagMismatch(..)), "{error}");
+        assert_eq!(repo.head().unwrap().target(), Some(initial));
+        assert_eq!(std::fs::read_to_string(&manifest).unwrap(), "[package]\nname = \"test\"\nversion = \"0.1.0\"\n");
+        assert!(!changelog.exists());
+        assert!(repo.statuses(None).unwrap().is_empty());
+    }
+
+    #[test]
+    fn release_writes_commits_and_tags() {
+        let test_repo = TestRepo::new();
+        let repo = &test_repo.repo;
+        let manifest = test_repo.path().join("Cargo.toml");
+        let changelog = test_repo.path().join("CHANGELOG.md");
+        std::fs::write(&manifest, "[package]\nname = \"test\"\nversion = \"0.1.0\"\n").unwrap();
+        std::fs::write(&changelog, "# Release notes: 0.1.0\n").unwrap();
+        commit_paths(repo, &[&manifest, &changelog], "semrel: 0.1.0").unwrap();
+
+        let plan = ReleasePlan {
+            manifest_path: &manifest,
+            version: SimpleVersion::new(0, 2, 0),
+            changelog: Some(&changelog),
+            add: &[],
+            message: "semrel: 0.2.0",
+            tag_name: "v0.2.0",
+            notes: "# Release notes: 0.2.0",
+        };
+        let commit = plan.execute(repo).unwrap();
+
+        assert_eq!(repo.head().unwrap().target(), Some(commit));
+        assert_eq!(crate::find_tag_target(repo, "v0.2.0").unwrap(), Some(commit));
+        assert_eq!(std::fs::read_to_string(&changelog).unwrap(), "# Release notes: 0.2.0\n\n# Release notes: 0.1.0\n");
+        assert!(repo.statuses(None).unwrap().is_empty());
+    }
+}
@@ -6,10 +6,21 @@ mod manifest;
 mod semantic_release;
 mod version;
 
-pub use config::{BumpRuleConfig, DEFAULT_CONFIG_FILENAME, SemRelConfig, find_canonical_config_path, find_local_config_path, load_config};
-pub use conventional_commits::{CommitMessageParser, CommitType, ConventionalCommit, Rule};
-pub use errors::{BumpRuleParse, ConfigError, ConventionalCommitError, ManifestError, RepositoryError};
-pub use git::{ChangeLog, CommitGroup, CommitInfo, collect_changelog_commits_streaming, find_top_of_repo, get_changelog, get_recent_commit, get_repo, is_repo, prune_message, revwalk, top_of_repo};
+pub use config::{
+    BumpRuleConfig, DEFAULT_CONFIG_FILENAME, DEFAULT_TAG_FORMAT, GitmojiConfig, LintConfig, PatternConfig, SemRelConfig, StrictConfig, TypeConfig, find_canonical_config_path, find_local_config_path,
+    load_config,
+};
+pub use conventional_commits::{
+    BUILTIN_ALIASES, CommitMessageParser, CommitPattern, CommitType, CommitTypeAliases, ConventionalCommit, DEFAULT_GITMOJI, Gitmoji, LintPolicy, ParseDiagnostic, ParseOptions, Rule, SubjectCase,
+    TypeDefinition, lint_message, strip_comments,
+};
+pub use errors::{BumpRuleParse, ConfigError, ConventionalCommitError, LintViolation, ManifestError, RepositoryError};
+pub use git::{
+    BackfillStatus, ChangeLog, ChangeLogOptions, CommitGroup, CommitInfo, HistoryMode, HookKind, NOTES_REF, PathFilters, Release, ReleasePlan, StrictAllowlist, WorktreeSnapshot, amend_message,
+    apply_note_override, backfill_tags, check_strict, collect_changelog_commits_streaming, commit_paths, commits_in_range, create_tag, find_containing_release, find_tag_target, find_top_of_repo,
+    format_tag_name, get_changelog, get_changelog_with, get_recent_commit, get_release, get_releases, get_repo, get_version_at, hooks_dir, install_hooks, is_repo, parse_tag_name, preflight,
+    prune_message, read_note_override, resolve_commit, revwalk, signature, strict_violations, tag_release, top_of_repo, uninstall_hooks, write_note_override,
+};
 pub use manifest::{Manifest, ManifestStatic};
 pub use semantic_release::{BumpRule, build_default_rules, match_rule, parse_rules};
 pub use version::{SimpleVersion, Ver, VersionError};
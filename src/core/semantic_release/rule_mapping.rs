@@ -1,13 +1,14 @@
-use crate::{BumpRule, CommitType};
+use crate::{BumpRule, CommitType, CommitTypeAliases};
 
-pub fn parse_rules(rules: &[impl AsRef<str>]) -> anyhow::Result<impl Iterator<Item = (CommitType, BumpRule)> + '_> {
+/// Parses `type=bump` rules, reading each type through `aliases`
+pub fn parse_rules<'a>(rules: &'a [impl AsRef<str>], aliases: &CommitTypeAliases) -> anyhow::Result<impl Iterator<Item = (CommitType, BumpRule)> + 'a> {
     let parsed = rules
         .iter()
         .flat_map(|rule| rule.as_ref().split(','))
         .map(|rule| {
             let mut parts = rule.split('=').take(2);
             let commit_type = match parts.next() {
-                Some(ct) => CommitType::from(ct),
+                Some(ct) => aliases.resolve(ct),
                 None => anyhow::bail!("No rule found."),
             };
             let bump_rule = match parts.next() {
@@ -29,7 +30,9 @@ mod tests {
     #[case::build(vec!["build=major"], vec![(CommitType::Build, BumpRule::Major)])]
     #[case::build_fix(vec!["build=major,fix=minor"], vec![(CommitType::Build, BumpRule::Major), (CommitType::Fix, BumpRule::Minor)])]
     fn test_parse_rules(#[case] rules: Vec<&str>, #[case] expected: Vec<(CommitType, BumpRule)>) {
-        let rules = parse_rules(rules.as_slice()).unwrap().collect::<Vec<_>>();
+        let rules = parse_rules(rules.as_slice(), &CommitTypeAliases::default())
+            .unwrap()
+            .collect::<Vec<_>>();
         assert_eq!(rules.len(), expected.len(), "Rule count mismatch: got {}, expected {}", rules.len(), expected.len());
         for ((actual_commit_type, actual_bump_rule), (expected_commit_type, expected_commit_rule)) in rules.iter().zip(expected.iter()) {
             assert_eq!(actual_commit_type, expected_commit_type);
@@ -37,12 +40,23 @@ mod tests {
         }
     }
 
+    #[rstest]
+    #[case::builtin_alias(&[], vec![(CommitType::Cd, BumpRule::Minor)])]
+    #[case::disabled_alias(&["deploy"], vec![(CommitType::Custom("deploy".to_string()), BumpRule::Minor)])]
+    fn test_parse_rules_with_aliases(#[case] disabled: &[&str], #[case] expected: Vec<(CommitType, BumpRule)>) {
+        let aliases = CommitTypeAliases::new([], disabled.iter().map(|alias| alias.to_string()));
+
+        let rules = parse_rules(&["deploy=minor"], &aliases).unwrap().collect::<Vec<_>>();
+
+        assert_eq!(rules, expected);
+    }
+
     #[rstest]
     #[case::invalid_bump(vec!["build=invalid"])]
     #[case::missing_bump(vec!["build"])]
     #[case::valid_then_invalid(vec!["build=major,fix=invalid"])]
     fn test_parse_rules_errors(#[case] rules: Vec<&str>) {
-        let result = parse_rules(rules.as_slice());
+        let result = parse_rules(rules.as_slice(), &CommitTypeAliases::default());
         assert!(result.is_err(), "Expected error for rules: {rules:?}");
     }
 }
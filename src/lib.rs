@@ -1,9 +1,13 @@
 mod core;
 pub use core::{
-    BumpRule, BumpRuleConfig, BumpRuleParse, ChangeLog, CommitGroup, CommitInfo, CommitMessageParser, CommitType, ConfigError, ConventionalCommit, ConventionalCommitError, DEFAULT_CONFIG_FILENAME,
-    Manifest, ManifestError, ManifestStatic, RepositoryError, Rule, SemRelConfig, SimpleVersion, Ver, VersionError, build_default_rules, collect_changelog_commits_streaming,
-    find_canonical_config_path, find_local_config_path, find_top_of_repo, get_changelog, get_recent_commit, get_repo, is_repo, load_config, match_rule, parse_rules, prune_message, revwalk,
-    top_of_repo,
+    BUILTIN_ALIASES, BackfillStatus, BumpRule, BumpRuleConfig, BumpRuleParse, ChangeLog, ChangeLogOptions, CommitGroup, CommitInfo, CommitMessageParser, CommitPattern, CommitType, CommitTypeAliases,
+    ConfigError, ConventionalCommit, ConventionalCommitError, DEFAULT_CONFIG_FILENAME, DEFAULT_GITMOJI, DEFAULT_TAG_FORMAT, Gitmoji, GitmojiConfig, HistoryMode, HookKind, LintConfig, LintPolicy,
+    LintViolation, Manifest, ManifestError, ManifestStatic, NOTES_REF, ParseDiagnostic, ParseOptions, PathFilters, PatternConfig, Release, ReleasePlan, RepositoryError, Rule, SemRelConfig,
+    SimpleVersion, StrictAllowlist, StrictConfig, SubjectCase, TypeConfig, TypeDefinition, Ver, VersionError, WorktreeSnapshot, amend_message, apply_note_override, backfill_tags, build_default_rules,
+    check_strict, collect_changelog_commits_streaming, commit_paths, commits_in_range, create_tag, find_canonical_config_path, find_containing_release, find_local_config_path, find_tag_target,
+    find_top_of_repo, format_tag_name, get_changelog, get_changelog_with, get_recent_commit, get_release, get_releases, get_repo, get_version_at, hooks_dir, install_hooks, is_repo, lint_message,
+    load_config, match_rule, parse_rules, parse_tag_name, preflight, prune_message, read_note_override, resolve_commit, revwalk, signature, strict_violations, strip_comments, tag_release,
+    top_of_repo, uninstall_hooks, write_note_override,
 };
 
 mod manifests;
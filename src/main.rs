@@ -1,4 +1,4 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use clap::Parser;
 use tracing_subscriber::EnvFilter;
@@ -20,19 +20,121 @@ pub struct Opts {
     /// Specify the configuration path
     #[clap(long, global = true, env = "SEMREL_CONFIG_PATH")]
     config_path: Option<PathBuf>,
+    /// How merge commits are traversed
+    #[clap(long, global = true, env = "SEMREL_HISTORY")]
+    history: Option<HistoryMode>,
 
     #[clap(subcommand)]
     pub cmd: Command,
 }
 
+/// The revision range read by the commands that only report on the history
+#[derive(Debug, Default, Clone, clap::Args)]
+pub struct RangeOpts {
+    /// Collect commits after this revision instead of after the previous release
+    #[clap(long, global = true)]
+    from: Option<String>,
+    /// Collect commits up to this revision instead of HEAD
+    #[clap(long, global = true)]
+    to: Option<String>,
+}
+
+/// Strict mode, for the commands that compute or write the next version
+#[derive(Debug, Default, Clone, Copy, clap::Args)]
+pub struct StrictOpts {
+    /// Fail when a change is not a conventional commit
+    #[clap(long, env = "SEMREL_STRICT")]
+    strict: bool,
+}
+
 #[derive(Debug, clap::Subcommand)]
 pub enum Command {
     /// Update the manifest
-    Update,
+    Update {
+        /// Skip the pre-flight checks (clean working tree, release branch, untagged HEAD)
+        #[clap(long)]
+        force: bool,
+        #[clap(flatten)]
+        strict: StrictOpts,
+    },
+    /// Update the manifest, commit and tag the release
+    Release {
+        /// Skip the pre-flight checks (clean working tree, release branch, untagged HEAD)
+        #[clap(long)]
+        force: bool,
+        /// Prepend the release notes to this changelog file and include it in the release commit
+        #[clap(long)]
+        changelog: Option<PathBuf>,
+        /// Additional files to include in the release commit (e.g. a lock file)
+        #[clap(long = "add")]
+        add: Vec<PathBuf>,
+        #[clap(flatten)]
+        strict: StrictOpts,
+    },
+    /// Show the release notes of every past release
+    History {
+        /// Only show the most recent releases
+        #[clap(short = 'n', long)]
+        limit: Option<usize>,
+        #[clap(flatten)]
+        range: RangeOpts,
+    },
+    /// Create an annotated release tag
+    #[clap(alias = "tags", args_conflicts_with_subcommands = true)]
+    Tag {
+        /// Tag the release that contains this commit
+        #[clap(long, default_value = "HEAD")]
+        rev: String,
+        #[clap(subcommand)]
+        cmd: Option<TagOpts>,
+    },
+    /// Show the first release that contains a commit
+    WhichRelease {
+        /// The commit to look up
+        sha: String,
+    },
+    /// Correct the type, scope or bump of a pushed commit with a note in refs/notes/semrel
+    Amend {
+        /// The commit to correct
+        sha: String,
+        /// The corrected commit type
+        #[clap(long = "type")]
+        commit_type: Option<String>,
+        /// The corrected scope (empty to remove it)
+        #[clap(long)]
+        scope: Option<String>,
+        /// Override the bump for this commit (none, patch, minor or major)
+        #[clap(long)]
+        semrel_bump: Option<BumpRule>,
+        /// The full corrected commit message, used in place of the current one
+        #[clap(short, long)]
+        message: Option<String>,
+    },
+    /// Check commit messages against the conventional commit format
+    Lint {
+        /// File holding the commit message (stdin when omitted or `-`)
+        file: Option<PathBuf>,
+        /// Check every non-merge commit in a revision range instead (e.g. origin/main..HEAD)
+        #[clap(long, conflicts_with = "file")]
+        range: Option<String>,
+        /// Require a scope on every commit
+        #[clap(long)]
+        require_scope: bool,
+        /// How to report problems
+        #[clap(long, value_enum, default_value_t = LintFormat::Text)]
+        format: LintFormat,
+    },
+    /// Manage the git hooks that lint commit messages
+    Hook {
+        #[clap(subcommand)]
+        cmd: HookOpts,
+    },
     /// Show information
     Show {
         #[clap(subcommand)]
         cmd: ShowOpts,
+        #[clap(flatten)]
+        range: RangeOpts,
     },
     /// Config subcommand
     Config {
@@ -45,8 +147,17 @@ pub enum Command {
 pub enum ShowOpts {
     /// Show only the current version
     Current,
+    /// Show the manifest version at a revision
+    Version {
+        /// The revision to read the manifest at
+        #[clap(long, default_value = "HEAD")]
+        at: String,
+    },
     /// Show the next version
-    Next,
+    Next {
+        #[clap(flatten)]
+        strict: StrictOpts,
+    },
     /// Show the changelog
     Log,
     /// Show the release notes
@@ -61,6 +172,57 @@ pub enum ShowOpts {
     ReleaseCommit,
 }
 
+impl Command {
+    /// Whether the command runs when the configuration file does not load
+    fn tolerates_invalid_config(&self) -> bool {
+        matches!(self, Command::Config { .. } | Command::Show { cmd: ShowOpts::Config, .. })
+    }
+}
+
+impl ShowOpts {
+    /// Whether the output depends on the changes since the previous release
+    fn reads_changes(&self) -> bool {
+        matches!(self, ShowOpts::Next { .. } | ShowOpts::Log | ShowOpts::Notes | ShowOpts::ReleaseCommit)
+    }
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub enum TagOpts {
+    /// Tag every past release that has no tag yet
+    Backfill {
+        /// Only print the tags that would be created
+        #[clap(long)]
+        dry_run: bool,
+    },
+}
+
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+pub enum LintFormat {
+    /// Human readable, with a caret under parse errors
+    Text,
+    /// One JSON object per line for each message (JSON Lines), for editor integrations
+    Json,
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub enum HookOpts {
+    /// Install a commit-msg hook that runs `semrel lint`
+    Install {
+        /// Also install a prepare-commit-msg hook that adds a reminder of the message format
+        #[clap(long)]
+        prepare_commit_msg: bool,
+        /// Replace hooks that were not installed by semrel
+        #[clap(long)]
+        force: bool,
+    },
+    /// Remove the hooks installed by semrel
+    Uninstall {
+        /// Also remove hooks that were not installed by semrel
+        #[clap(long)]
+        force: bool,
+    },
+}
+
 #[derive(Debug, clap::Subcommand)]
 pub enum ConfigOpts {
     /// Edit the current configuration
@@ -68,6 +230,9 @@ pub enum ConfigOpts {
 }
 
 struct CliData {
+    repo: git2::Repository,
+    config: SemRelConfig,
+    changelog_options: ChangeLogOptions,
     manifest_path: PathBuf,
     rules: Vec<(CommitType, BumpRule)>,
     config_path: Option<PathBuf>,
@@ -99,69 +264,346 @@ fn main() -> anyhow::Result<()> {
             None => None,
         },
     };
-    let config_rules = match &config_path {
-        Some(path) => match load_config(path) {
-            Ok(config) => {
-                let rules = config.rules().into_iter().collect::<Vec<_>>();
-                tracing::info!("Loaded config: {} with {} rules", path.display(), rules.len());
-                rules
-            }
-            Err(why) => {
-                tracing::error!("Error loading config: {why}");
-                SemRelConfig::default().rules().into_iter().collect::<Vec<_>>()
-            }
-        },
+    let config = match &config_path {
+        // `config edit` repairs a broken configuration and `show config` reports why it does not load
+        Some(path) if opts.cmd.tolerates_invalid_config() => load_config(path).unwrap_or_else(|why| {
+            tracing::warn!("{}: {why}", path.display());
+            SemRelConfig::default()
+        }),
+        Some(path) => {
+            // A broken configuration must never fall back to the defaults
+            let config = load_config(path).map_err(|why| anyhow::anyhow!("{}: {why}", path.display()))?;
+            tracing::info!("Loaded config: {} with {} rules", path.display(), config.rules().into_iter().count());
+            config
+        }
         None => {
             tracing::info!("Using default rules for configuration.");
-            SemRelConfig::default().rules().into_iter().collect::<Vec<_>>()
+            SemRelConfig::default()
         }
     };
-    let rules = parse_rules(&opts.rule)?
+    let config_rules = config.rules().into_iter().collect::<Vec<_>>();
+    let aliases = config.aliases();
+    // Rules name commit types the same way commit messages do, so `bugfix=minor` follows a `bugfix` alias
+    let rules = parse_rules(&opts.rule, &aliases)?
         .chain(config_rules)
+        .chain(config.type_rules())
         .chain(build_default_rules())
         .collect::<Vec<_>>();
     tracing::info!("Active rules: {}", rules.len());
     for (commit_type, bump_rule) in rules.iter() {
         tracing::trace!(" - Active: {commit_type:?} -> {bump_rule:?}");
     }
-    let manifest_path = find_manifest(path)?;
-    let changelog = get_changelog(&repo, &manifest_path)?;
-    tracing::info!("Found manifest: {}", manifest_path.display());
-    let current_version = changelog.current_version;
-    tracing::info!("Found manifest version: {current_version}");
-    let bump = opts.bump.unwrap_or_default();
-    tracing::info!("Found bump rule: {bump}");
-    let new_version = match bump {
-        BumpRule::Notset => changelog.next_version(&rules),
-        _ => changelog.current_version.bump(bump),
-    };
-    tracing::info!("Calculated new version: {new_version}");
-
-    let cli_data = CliData {
-        manifest_path,
-        rules: rules.to_vec(),
+    let context = CliContext {
+        repo,
+        config,
         config_path,
-        changelog,
-        new_version,
-        current_version,
+        rules,
+        history: opts.history,
+        bump: opts.bump.unwrap_or_default(),
+        path: path.clone(),
     };
 
+    // Linting and hooks only need the rules, so they work in repositories without a manifest
     match opts.cmd {
-        Command::Update => handle_update(&cli_data),
-        Command::Show { cmd } => handle_show_command(cmd, &cli_data),
-        Command::Config { cmd } => handle_config_command(cmd, &cli_data),
+        Command::Lint { file, range, require_scope, format } => {
+            let mut policy = context.config.lint_policy(&context.rules);
+            policy.require_scope |= require_scope;
+            handle_lint(&context.repo, file.as_deref(), range.as_deref(), &policy, format)
+        }
+        Command::Hook { cmd } => handle_hook_command(&cmd, &context.repo),
+        Command::Update { force, strict } => handle_update(force, strict, &context.load(&RangeOpts::default(), false)?),
+        Command::Release { force, changelog, add, strict } => handle_release(force, strict, changelog, add, &context.load(&RangeOpts::default(), false)?),
+        Command::History { limit, range } => handle_history(limit, &context.load(&range, true)?),
+        Command::Tag { rev, cmd } => handle_tag(&rev, cmd, &context.load(&RangeOpts::default(), false)?),
+        Command::WhichRelease { sha } => handle_which_release(&sha, &context.load(&RangeOpts::default(), true)?),
+        Command::Amend {
+            sha,
+            commit_type,
+            scope,
+            semrel_bump,
+            message,
+        } => handle_amend(&sha, commit_type.as_deref(), scope.as_deref(), semrel_bump, message, &context.load(&RangeOpts::default(), true)?),
+        Command::Show { cmd, range } => {
+            let allow_shallow = !cmd.reads_changes();
+            handle_show_command(cmd, &context.load(&range, allow_shallow)?)
+        }
+        Command::Config { cmd } => handle_config_command(cmd, &context.load(&RangeOpts::default(), true)?),
+    }
+}
+
+/// What every command needs before a manifest or changelog is read
+struct CliContext {
+    repo: git2::Repository,
+    config: SemRelConfig,
+    config_path: Option<PathBuf>,
+    rules: Vec<(CommitType, BumpRule)>,
+    history: Option<HistoryMode>,
+    bump: BumpRule,
+    path: String,
+}
+
+impl CliContext {
+    /// Finds the manifest and computes the changelog over `range`
+    ///
+    /// With `allow_shallow`, a shallow clone that does not reach the previous release is not an
+    /// error; commands that only read the manifest or look up past releases pass it.
+    fn load(self, range: &RangeOpts, allow_shallow: bool) -> anyhow::Result<CliData> {
+        let repo = self.repo;
+        let config = self.config;
+        let manifest_path = find_manifest(&self.path)?;
+        let changelog_options = ChangeLogOptions {
+            history: self.history.unwrap_or(config.history()),
+            from: range.from.as_ref().map(|rev| resolve_commit(&repo, rev)).transpose()?,
+            to: range.to.as_ref().map(|rev| resolve_commit(&repo, rev)).transpose()?,
+            paths: config.path_filters(),
+            parsing: config.parse_options(),
+            types: config.types(),
+            allow_shallow,
+        };
+        let changelog = get_changelog_with(&repo, &manifest_path, &changelog_options)?;
+        tracing::info!("Found manifest: {}", manifest_path.display());
+        let current_version = changelog.current_version;
+        tracing::info!("Found manifest version: {current_version}");
+        tracing::info!("Found bump rule: {}", self.bump);
+        let new_version = match self.bump {
+            BumpRule::Notset => changelog.next_version(&self.rules),
+            bump => changelog.current_version.bump(bump),
+        };
+        tracing::info!("Calculated new version: {new_version}");
+
+        Ok(CliData {
+            repo,
+            config,
+            changelog_options,
+            manifest_path,
+            rules: self.rules,
+            config_path: self.config_path,
+            changelog,
+            new_version,
+            current_version,
+        })
     }
 }
 
-fn handle_update(cli_data: &CliData) -> anyhow::Result<()> {
+fn handle_update(force: bool, strict: StrictOpts, cli_data: &CliData) -> anyhow::Result<()> {
+    check_preflight(force, cli_data)?;
+    check_strict_mode(strict, cli_data)?;
+    write_manifest(cli_data)?;
+    println!("Wrote to: {}", cli_data.manifest_path.display());
+    Ok(())
+}
+
+fn write_manifest(cli_data: &CliData) -> anyhow::Result<()> {
     let manifest_data = std::fs::read(&cli_data.manifest_path).map_err(|why| anyhow::anyhow!("failed to read manifest {}: {why}", cli_data.manifest_path.display()))?;
     let data = String::from_utf8(manifest_data).map_err(|why| anyhow::anyhow!("manifest {} is not valid UTF-8: {why}", cli_data.manifest_path.display()))?;
     let mut supported_manifest = SupportedManifest::parse(&cli_data.manifest_path, data)?;
     supported_manifest.set_version(cli_data.new_version)?;
     supported_manifest.write(&cli_data.manifest_path)?;
-    println!("Wrote to: {}", cli_data.manifest_path.display());
     Ok(())
 }
+
+fn check_preflight(force: bool, cli_data: &CliData) -> anyhow::Result<()> {
+    if force {
+        tracing::warn!("Skipping pre-flight checks");
+        return Ok(());
+    }
+    preflight(&cli_data.repo, cli_data.config.release_branches(), cli_data.config.tag_format()).map_err(|why| anyhow::anyhow!("{why}.  Use --force to skip this check"))
+}
+
+fn check_strict_mode(opts: StrictOpts, cli_data: &CliData) -> anyhow::Result<()> {
+    if !opts.strict && !cli_data.config.strict() {
+        return Ok(());
+    }
+    check_strict(&cli_data.changelog, &cli_data.rules, &cli_data.config.strict_allowlist())?;
+    Ok(())
+}
+
+fn handle_release(force: bool, strict: StrictOpts, changelog_path: Option<PathBuf>, add: Vec<PathBuf>, cli_data: &CliData) -> anyhow::Result<()> {
+    check_preflight(force, cli_data)?;
+    check_strict_mode(strict, cli_data)?;
+    if cli_data.new_version == cli_data.current_version {
+        return Err(anyhow::anyhow!("Nothing to release: version remains {}", cli_data.current_version));
+    }
+    let notes = release_notes(cli_data);
+    let message = release_commit_message(cli_data, &notes);
+    let tag_name = format_tag_name(cli_data.config.tag_format(), cli_data.new_version);
+    let plan = ReleasePlan {
+        manifest_path: &cli_data.manifest_path,
+        version: cli_data.new_version,
+        changelog: changelog_path.as_deref(),
+        add: &add,
+        message: &message,
+        tag_name: &tag_name,
+        notes: &notes,
+    };
+    plan.execute(&cli_data.repo)?;
+    println!("Released {} as {tag_name}", cli_data.new_version);
+    Ok(())
+}
+
+fn handle_history(limit: Option<usize>, cli_data: &CliData) -> anyhow::Result<()> {
+    let releases = get_releases(&cli_data.repo, &cli_data.manifest_path, &cli_data.changelog_options, limit)?;
+    let notes = releases.iter().map(|release| release.release_notes()).collect::<Vec<_>>();
+    println!("{}", notes.join("\n\n"));
+    Ok(())
+}
+
+/// The notes for the release of `new_version`, which `--bump` may set apart from the rules
+fn release_notes(cli_data: &CliData) -> String {
+    cli_data
+        .changelog
+        .release_notes_for(cli_data.new_version, chrono::Local::now().date_naive())
+}
+
+fn release_commit_message(cli_data: &CliData, release_notes: &str) -> String {
+    let ignored = ["semrel"];
+    let log = &cli_data
+        .changelog
+        .changes
+        .iter()
+        .filter(|item| !ignored.iter().any(|s| item.commit_type().as_str().starts_with(s)))
+        .map(|item| format!("{} {}", item.id, item.message()))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let next_version = &cli_data.new_version;
+    format!("semrel: {next_version}\n\n{release_notes}\n\n# Log\n{log}\n")
+}
+
+fn handle_tag(rev: &str, cmd: Option<TagOpts>, cli_data: &CliData) -> anyhow::Result<()> {
+    match cmd {
+        None => {
+            let rev = resolve_commit(&cli_data.repo, rev)?;
+            let (tag_name, target) = tag_release(&cli_data.repo, &cli_data.manifest_path, rev, cli_data.config.tag_format(), &cli_data.changelog_options)?;
+            println!("Created tag: {tag_name} at {target}");
+        }
+        Some(TagOpts::Backfill { dry_run }) => {
+            let tags = backfill_tags(&cli_data.repo, &cli_data.manifest_path, cli_data.config.tag_format(), &cli_data.changelog_options, dry_run)?;
+            for (tag_name, target, status) in tags {
+                match status {
+                    BackfillStatus::Exists => println!("Exists: {tag_name}"),
+                    BackfillStatus::Conflict(existing) => println!("Skipped: {tag_name} points to {existing}, not {target}"),
+                    BackfillStatus::Missing => println!("Would create tag: {tag_name} at {target}"),
+                    BackfillStatus::Created => println!("Created tag: {tag_name} at {target}"),
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn handle_which_release(sha: &str, cli_data: &CliData) -> anyhow::Result<()> {
+    let commit = resolve_commit(&cli_data.repo, sha)?;
+    match find_containing_release(&cli_data.repo, &cli_data.manifest_path, commit, &cli_data.changelog_options)? {
+        Some(release) => println!("{} {} {}", release.version, release.id, release.date().format("%Y-%m-%d")),
+        None => println!("Unreleased: {commit}"),
+    }
+    Ok(())
+}
+
+fn handle_amend(sha: &str, commit_type: Option<&str>, scope: Option<&str>, bump: Option<BumpRule>, message: Option<String>, cli_data: &CliData) -> anyhow::Result<()> {
+    let oid = resolve_commit(&cli_data.repo, sha)?;
+    let message = match message {
+        Some(message) => message,
+        None => {
+            let commit = cli_data.repo.find_commit(oid)?;
+            let original = commit.message().unwrap_or_default();
+            match read_note_override(&cli_data.repo, oid) {
+                Some(note) => apply_note_override(original, &note),
+                None => original.to_string(),
+            }
+        }
+    };
+    let amended = amend_message(&message, commit_type, scope, bump, &cli_data.changelog_options.parsing)?;
+    write_note_override(&cli_data.repo, oid, &amended)?;
+    println!("Amended {oid}: {}", amended.lines().next().unwrap_or_default());
+    println!("Share it with: git push origin {NOTES_REF}");
+    Ok(())
+}
+
+fn handle_lint(repo: &git2::Repository, file: Option<&Path>, range: Option<&str>, policy: &LintPolicy, format: LintFormat) -> anyhow::Result<()> {
+    let messages = match range {
+        Some(range) => commits_in_range(repo, range)?
+            .into_iter()
+            .map(|oid| {
+                let commit = repo.find_commit(oid)?;
+                Ok((oid.to_string()[..7].to_string(), commit.message().unwrap_or_default().to_string()))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?,
+        None => {
+            let (source, message) = match file {
+                Some(path) if path != Path::new("-") => {
+                    let message = std::fs::read_to_string(path).map_err(|why| anyhow::anyhow!("failed to read {}: {why}", path.display()))?;
+                    (path.display().to_string(), message)
+                }
+                _ => ("stdin".to_string(), std::io::read_to_string(std::io::stdin())?),
+            };
+            vec![(source, strip_comments(&message))]
+        }
+    };
+    let mut failed = 0;
+    for (source, message) in &messages {
+        let violations = lint_message(message, policy);
+        if !violations.is_empty() {
+            failed += 1;
+        }
+        match format {
+            LintFormat::Json => {
+                let violations = violations
+                    .iter()
+                    .map(|violation| serde_json::json!({ "message": violation.to_string(), "diagnostic": violation.diagnostic(message) }))
+                    .collect::<Vec<_>>();
+                let report = serde_json::json!({ "source": source, "subject": message.lines().next().unwrap_or_default(), "violations": violations });
+                println!("{}", serde_json::to_string(&report)?);
+            }
+            LintFormat::Text if violations.is_empty() => {}
+            LintFormat::Text => {
+                eprintln!("{source}: {}", message.lines().next().unwrap_or_default());
+                for violation in violations {
+                    match violation.diagnostic(message) {
+                        Some(diagnostic) => eprintln!("{}", indent(&diagnostic.render(), "    ")),
+                        None => eprintln!("  - {violation}"),
+                    }
+                }
+            }
+        }
+    }
+    match failed {
+        0 => Ok(()),
+        _ => Err(anyhow::anyhow!("{failed} of {} commit message(s) failed lint", messages.len())),
+    }
+}
+
+fn indent(text: &str, prefix: &str) -> String {
+    text.lines()
+        .map(|line| format!("{prefix}{line}"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn handle_hook_command(cmd: &HookOpts, repo: &git2::Repository) -> anyhow::Result<()> {
+    match cmd {
+        HookOpts::Install { prepare_commit_msg, force } => {
+            let mut kinds = vec![HookKind::CommitMsg];
+            if *prepare_commit_msg {
+                kinds.push(HookKind::PrepareCommitMsg);
+            }
+            for path in install_hooks(repo, &kinds, *force)? {
+                println!("Installed hook: {}", path.display());
+            }
+        }
+        HookOpts::Uninstall { force } => {
+            let removed = uninstall_hooks(repo, &[HookKind::CommitMsg, HookKind::PrepareCommitMsg], *force)?;
+            if removed.is_empty() {
+                println!("No semrel hooks installed in {}", hooks_dir(repo).display());
+            }
+            for path in removed {
+                println!("Removed hook: {}", path.display());
+            }
+        }
+    }
+    Ok(())
+}
+
 fn handle_config_command(cmd: ConfigOpts, cli_data: &CliData) -> anyhow::Result<()> {
     match cmd {
         ConfigOpts::Edit => {
@@ -247,6 +689,13 @@ fn handle_show_command(cmd: ShowOpts, cli_data: &CliData) -> anyhow::Result<()>
             Ok(())
         }
         ShowOpts::Log => {
+            let paths = &cli_data.changelog_options.paths;
+            for pattern in &paths.include {
+                println!("# include: {pattern}");
+            }
+            for pattern in &paths.exclude {
+                println!("# exclude: {pattern}");
+            }
             // Do not show these because they are automatically generated and very verbose
             let ignored = ["semrel"];
             let _ = &cli_data
@@ -257,7 +706,8 @@ fn handle_show_command(cmd: ShowOpts, cli_data: &CliData) -> anyhow::Result<()>
                 .for_each(|item| println!("{} {}", item.id, item.message()));
             Ok(())
         }
-        ShowOpts::Next => {
+        ShowOpts::Next { strict } => {
+            check_strict_mode(strict, cli_data)?;
             println!("{}", cli_data.new_version);
             Ok(())
         }
@@ -265,19 +715,13 @@ fn handle_show_command(cmd: ShowOpts, cli_data: &CliData) -> anyhow::Result<()>
             println!("{}", cli_data.current_version);
             Ok(())
         }
+        ShowOpts::Version { at } => {
+            let commit = resolve_commit(&cli_data.repo, at)?;
+            println!("{}", get_version_at(&cli_data.repo, &cli_data.manifest_path, commit)?);
+            Ok(())
+        }
         ShowOpts::ReleaseCommit => {
-            let ignored = ["semrel"];
-            let release_notes = &cli_data.changelog.release_notes(&cli_data.rules);
-            let log = &cli_data
-                .changelog
-                .changes
-                .iter()
-                .filter(|item| !ignored.iter().any(|s| item.commit_type().as_str().starts_with(s)))
-                .map(|item| format!("{} {}", item.id, item.message()))
-                .collect::<Vec<_>>()
-                .join("\n");
-            let next_version = &cli_data.new_version;
-            println!("semrel: {next_version}\n\n{release_notes}\n\n# Log\n{log}\n");
+            println!("{}", release_commit_message(cli_data, &release_notes(cli_data)));
             Ok(())
         }
     }